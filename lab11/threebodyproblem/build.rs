@@ -0,0 +1,24 @@
+use std::process::Command;
+
+// Uruchamia gita i zwraca pierwszą linię wyniku (None, jeśli git nie zadziałał)
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Rewizja kodu, z którego zbudowano program - zapisywana w manifestach przebiegów
+fn main() {
+    let revision = git(&["rev-parse", "--short=12", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    // Niezatwierdzone zmiany w śledzonych plikach tego projektu
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no", "--", "."]).is_some_and(|s| !s.is_empty());
+    println!("cargo:rustc-env=GIT_REVISION={}{}", revision, if dirty { "-dirty" } else { "" });
+
+    println!("cargo:rerun-if-changed=src");
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        println!("cargo:rerun-if-changed={}/HEAD", git_dir);
+        println!("cargo:rerun-if-changed={}/index", git_dir);
+    }
+}
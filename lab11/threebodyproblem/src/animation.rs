@@ -0,0 +1,259 @@
+use plotters::prelude::*;
+use gif::{Frame, Encoder, Repeat};
+use rayon::prelude::*;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use crate::camera::{self, Camera, Viewport};
+use crate::overlay::{OverlayData, Overlays};
+use crate::physics::Gravity;
+use crate::sampling::{sample_uniform, Sample};
+use crate::trail::{TrailColor, TrailMode, Trails};
+
+const BODY_RADIUS: i32 = 5;  // Rozmiar kul
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 600;
+const BODY_COLORS: [RGBColor; 3] = [RED, BLUE, GREEN];
+
+type RenderError = Box<dyn std::error::Error + Send + Sync>;
+
+// Format pliku wynikowego animacji
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnimationFormat {
+    Gif,         // 256 kolorów na klatkę
+    Apng,        // animowany PNG, pełny kolor
+    PngSequence, // katalog z ponumerowanymi klatkami PNG (np. do złożenia w wideo)
+}
+
+impl AnimationFormat {
+    pub fn parse(name: &str) -> Option<AnimationFormat> {
+        match name {
+            "gif" => Some(AnimationFormat::Gif),
+            "apng" => Some(AnimationFormat::Apng),
+            "png-seq" => Some(AnimationFormat::PngSequence),
+            _ => None,
+        }
+    }
+
+    // Format wynikający z nazwy: .gif, .png/.apng, a bez rozszerzenia - katalog z klatkami
+    pub fn from_path(path: &str) -> Option<AnimationFormat> {
+        match Path::new(path).extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("gif") => Some(AnimationFormat::Gif),
+            Some(ext) if ext.eq_ignore_ascii_case("png") || ext.eq_ignore_ascii_case("apng") => {
+                Some(AnimationFormat::Apng)
+            }
+            Some(_) => None,
+            None => Some(AnimationFormat::PngSequence),
+        }
+    }
+
+    // Domyślna nazwa pliku (lub katalogu) wynikowego
+    pub fn default_output(&self, timestamp: &str) -> String {
+        match self {
+            AnimationFormat::Gif => format!("three_body_animation_{}.gif", timestamp),
+            AnimationFormat::Apng => format!("three_body_animation_{}.png", timestamp),
+            AnimationFormat::PngSequence => format!("three_body_frames_{}", timestamp),
+        }
+    }
+}
+
+// Ustawienia animacji
+pub struct AnimationOptions {
+    pub format: AnimationFormat,
+    pub fps: f64,
+    pub time_per_second: f64, // ile jednostek czasu fizycznego trwa sekunda animacji
+    pub trail_mode: TrailMode,
+    pub trail_color: TrailColor,
+    pub camera: Camera,
+    pub overlays: Overlays,
+    pub gravity: Gravity, // model, którym policzono przebieg (energia w śladach i nakładkach)
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        AnimationOptions {
+            format: AnimationFormat::Gif,
+            fps: 20.0,
+            time_per_second: 0.4,
+            trail_mode: TrailMode::Full,
+            trail_color: TrailColor::Body,
+            camera: Camera::Global,
+            overlays: Overlays::default(),
+            gravity: Gravity::Newton,
+        }
+    }
+}
+
+// Rysuje jedną klatkę do bufora RGB w pamięci
+fn render_frame(sample: &Sample, frame: usize, trails: &Trails, overlays: &OverlayData, view: &Viewport) -> Result<Vec<u8>, RenderError> {
+    let mut buffer = vec![0u8; (WIDTH * HEIGHT * 3) as usize];
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (WIDTH, HEIGHT)).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        let state = &sample.state;
+
+        // Przygotuj wykres
+        let mut chart = ChartBuilder::on(&root)
+            .caption(format!("Symulacja 3 ciał (t = {:.2})", sample.time), ("sans-serif", 20))
+            .margin(20)
+            .x_label_area_size(40)
+            .y_label_area_size(40)
+            .build_cartesian_2d(view.x_min..view.x_max, view.y_min..view.y_max)?;
+
+        chart.configure_mesh().draw()?;
+
+        // Rysuj tory
+        trails.draw(&mut chart, 0, RED, frame)?;
+        trails.draw(&mut chart, 1, BLUE, frame)?;
+        trails.draw(&mut chart, 2, GREEN, frame)?;
+
+        // Rysuj ciała jako koła
+        chart.draw_series(std::iter::once(Circle::new(
+            (state[0], state[1]),
+            BODY_RADIUS,
+            RED.filled(),
+        )))?;
+        chart.draw_series(std::iter::once(Circle::new(
+            (state[2], state[3]),
+            BODY_RADIUS,
+            BLUE.filled(),
+        )))?;
+        chart.draw_series(std::iter::once(Circle::new(
+            (state[4], state[5]),
+            BODY_RADIUS,
+            GREEN.filled(),
+        )))?;
+
+        overlays.draw(&root, &chart, sample, frame, &BODY_COLORS)?;
+
+        root.present()?;
+    }
+    Ok(buffer)
+}
+
+// Klatka przygotowana do zapisu. Kosztowne kroki (kwantyzacja GIF, kodowanie
+// pojedynczych plików PNG) wykonujemy równolegle, przed zapisem.
+enum PreparedFrame {
+    Gif(Frame<'static>),
+    Rgb(Vec<u8>),
+    Saved,
+}
+
+// Zapisuje kolejne klatki w wybranym formacie
+enum FrameWriter {
+    Gif(Encoder<File>),
+    Apng(png::Writer<BufWriter<File>>),
+    PngSequence,
+}
+
+// Ścieżka klatki w katalogu z sekwencją PNG
+fn sequence_frame_path(directory: &str, frame: usize) -> PathBuf {
+    Path::new(directory).join(format!("frame_{:05}.png", frame))
+}
+
+fn save_png(path: &Path, rgb: &[u8]) -> Result<(), RenderError> {
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), WIDTH, HEIGHT);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(rgb)?;
+    writer.finish()?;
+    Ok(())
+}
+
+pub fn create_animation(data: &[Vec<f64>], times: &[f64], filename: &str, options: &AnimationOptions) -> Result<(), Box<dyn std::error::Error>> {
+    // W układzie środka masy rysujemy przeliczone dane
+    let com_data;
+    let data = if options.camera == Camera::CenterOfMass {
+        com_data = camera::to_center_of_mass_frame(data);
+        &com_data[..]
+    } else {
+        data
+    };
+
+    // Klatki w równych odstępach czasu fizycznego, niezależnie od kroku integratora
+    let samples = sample_uniform(data, times, options.time_per_second / options.fps);
+    let total_frames = samples.len();
+    let delay = (100.0 / options.fps).round().max(1.0) as u16; // w setnych sekundy
+
+    // Kadr każdej klatki zależy od poprzednich (wygładzanie), więc liczymy go z góry
+    let views = camera::viewports(options.camera, data, &samples, WIDTH as f64 / HEIGHT as f64);
+
+    // Ślady budujemy raz, sekwencyjnie - klatki tylko czytają swoje wycinki.
+    // Rozdzielczość śladu dobieramy do najmniejszego kadru.
+    let extent = views.iter().map(|v| v.extent()).fold(f64::INFINITY, f64::min);
+    let mut trails = Trails::new(data, extent * 1e-3, options.trail_mode, options.trail_color, options.gravity);
+    for sample in &samples {
+        trails.push_frame(data, sample);
+    }
+    let overlays = OverlayData::new(options.overlays, options.gravity, &data[0], &samples);
+
+    // Przygotuj zapis w wybranym formacie
+    let mut writer = match options.format {
+        AnimationFormat::Gif => {
+            let mut encoder = Encoder::new(File::create(filename)?, WIDTH as u16, HEIGHT as u16, &[])?;
+            encoder.set_repeat(Repeat::Infinite)?;
+            FrameWriter::Gif(encoder)
+        }
+        AnimationFormat::Apng => {
+            let mut encoder = png::Encoder::new(BufWriter::new(File::create(filename)?), WIDTH, HEIGHT);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.set_animated(total_frames as u32, 0)?;
+            encoder.set_frame_delay(delay, 100)?;
+            FrameWriter::Apng(encoder.write_header()?)
+        }
+        AnimationFormat::PngSequence => {
+            fs::create_dir_all(filename)?;
+            FrameWriter::PngSequence
+        }
+    };
+
+    // Klatki rysujemy i kwantyzujemy równolegle w paczkach, a zapisujemy po kolei,
+    // żeby w pamięci trzymać tylko jedną paczkę naraz
+    let batch_size = rayon::current_num_threads() * 4;
+    let mut written = 0;
+    for (batch_index, batch) in samples.chunks(batch_size).enumerate() {
+        let frames = batch
+            .par_iter()
+            .enumerate()
+            .map(|(i, sample)| {
+                let frame = batch_index * batch_size + i;
+                let rgb = render_frame(sample, frame, &trails, &overlays, &views[frame])?;
+                match options.format {
+                    AnimationFormat::Gif => {
+                        let mut gif_frame = Frame::from_rgb_speed(WIDTH as u16, HEIGHT as u16, &rgb, 10);
+                        gif_frame.delay = delay;
+                        Ok(PreparedFrame::Gif(gif_frame))
+                    }
+                    AnimationFormat::Apng => Ok(PreparedFrame::Rgb(rgb)),
+                    AnimationFormat::PngSequence => {
+                        save_png(&sequence_frame_path(filename, frame), &rgb)?;
+                        Ok(PreparedFrame::Saved)
+                    }
+                }
+            })
+            .collect::<Result<Vec<_>, RenderError>>()
+            .map_err(|e| e as Box<dyn std::error::Error>)?;
+
+        for frame in &frames {
+            if written % (total_frames / 10).max(1) == 0 {
+                println!("Generowanie klatki: {}/{}", written, total_frames);
+            }
+            match (&mut writer, frame) {
+                (FrameWriter::Gif(encoder), PreparedFrame::Gif(gif_frame)) => encoder.write_frame(gif_frame)?,
+                (FrameWriter::Apng(png_writer), PreparedFrame::Rgb(rgb)) => png_writer.write_image_data(rgb)?,
+                _ => {}
+            }
+            written += 1;
+        }
+    }
+
+    if let FrameWriter::Apng(png_writer) = writer {
+        png_writer.finish()?;
+    }
+
+    println!("Animacja zapisana: {}", filename);
+    Ok(())
+}
@@ -0,0 +1,304 @@
+use serde::Deserialize;
+use std::error::Error;
+use crate::physics::center_of_mass;
+use crate::sampling::Sample;
+
+// Widoczny fragment płaszczyzny w jednej klatce
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub struct Viewport {
+    pub x_min: f64,
+    pub x_max: f64,
+    pub y_min: f64,
+    pub y_max: f64,
+}
+
+impl Viewport {
+    fn centered(cx: f64, cy: f64, half_width: f64, half_height: f64) -> Self {
+        Viewport {
+            x_min: cx - half_width,
+            x_max: cx + half_width,
+            y_min: cy - half_height,
+            y_max: cy + half_height,
+        }
+    }
+
+    pub fn extent(&self) -> f64 {
+        (self.x_max - self.x_min).max(self.y_max - self.y_min)
+    }
+}
+
+// Sposób prowadzenia kamery w animacji
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Camera {
+    Global,                     // stały kadr obejmujący cały przebieg
+    CenterOfMass,               // układ środka masy
+    Follow(usize, Option<f64>), // kadr wyśrodkowany na ciele (opcjonalnie z szerokością)
+    AutoZoom,                   // kadr dopasowany do bieżącego położenia ciał, z wygładzaniem
+    Fixed(Viewport),            // kadr podany przez użytkownika
+}
+
+impl Camera {
+    // Format: "global", "com", "follow:N[:szerokość]", "zoom", "fixed:xmin,xmax,ymin,ymax"
+    pub fn parse(spec: &str) -> Option<Camera> {
+        let mut parts = spec.split(':');
+        let camera = match parts.next()? {
+            "global" => Camera::Global,
+            "com" => Camera::CenterOfMass,
+            "zoom" => Camera::AutoZoom,
+            "follow" => {
+                let body = parts.next()?.parse::<usize>().ok()?;
+                if !(1..=3).contains(&body) {
+                    return None;
+                }
+                let width = match parts.next() {
+                    Some(w) => Some(w.parse::<f64>().ok().filter(|w| *w > 0.0)?),
+                    None => None,
+                };
+                Camera::Follow(body - 1, width)
+            }
+            "fixed" => {
+                let limits: Vec<f64> = parts
+                    .next()?
+                    .split(',')
+                    .map(|v| v.trim().parse::<f64>())
+                    .collect::<Result<_, _>>()
+                    .ok()?;
+                match limits[..] {
+                    [x_min, x_max, y_min, y_max] if x_min < x_max && y_min < y_max => {
+                        Camera::Fixed(Viewport { x_min, x_max, y_min, y_max })
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        match parts.next() {
+            Some(_) => None,
+            None => Some(camera),
+        }
+    }
+}
+
+// Plik scenariusza (JSON) z ustawieniami kamery, np.
+//   {"camera": "follow:2:3.0"}
+//   {"camera": "fixed", "viewport": {"x_min": -2, "x_max": 2, "y_min": -1.5, "y_max": 1.5}}
+// Tryb zapisany jak w --camera; sam kadr bez trybu oznacza kamerę stałą.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub camera: Option<String>,
+    pub viewport: Option<Viewport>,
+}
+
+impl Scenario {
+    pub fn read(filename: &str) -> Result<Scenario, Box<dyn Error>> {
+        let text = std::fs::read_to_string(filename)?;
+        serde_json::from_str(&text).map_err(|e| format!("Niepoprawny scenariusz {}: {}", filename, e).into())
+    }
+
+    // Kamera opisana scenariuszem; None, gdy scenariusz jej nie ustawia
+    pub fn camera(&self) -> Result<Option<Camera>, String> {
+        let viewport = match self.viewport {
+            Some(v) if !(v.x_min < v.x_max && v.y_min < v.y_max) => {
+                return Err(format!("Niepoprawny kadr w scenariuszu: {:?}", v));
+            }
+            viewport => viewport,
+        };
+        match (self.camera.as_deref(), viewport) {
+            (None, None) => Ok(None),
+            (Some("fixed") | None, Some(viewport)) => Ok(Some(Camera::Fixed(viewport))),
+            (Some(spec), None) => Camera::parse(spec)
+                .map(Some)
+                .ok_or_else(|| format!("Nieznany tryb kamery w scenariuszu: {}", spec)),
+            (Some(spec), Some(_)) => Err(format!("Kadr w scenariuszu wymaga kamery \"fixed\", a nie: {}", spec)),
+        }
+    }
+}
+
+const MARGIN: f64 = 1.1;     // zapas wokół ciał
+const SMOOTHING: f64 = 0.05; // jak szybko kadr się zacieśnia (na klatkę)
+
+// Prostokąt obejmujący wszystkie ciała w danym stanie
+fn bounding_box(state: &[f64]) -> Viewport {
+    let xs = [state[0], state[2], state[4]];
+    let ys = [state[1], state[3], state[5]];
+    Viewport {
+        x_min: xs.iter().cloned().fold(f64::INFINITY, f64::min),
+        x_max: xs.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        y_min: ys.iter().cloned().fold(f64::INFINITY, f64::min),
+        y_max: ys.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+    }
+}
+
+// Kadr obejmujący wszystkie położenia z całego przebiegu
+pub fn global_viewport(data: &[Vec<f64>]) -> Viewport {
+    data.iter().map(|s| bounding_box(s)).fold(bounding_box(&data[0]), |a, b| Viewport {
+        x_min: a.x_min.min(b.x_min),
+        x_max: a.x_max.max(b.x_max),
+        y_min: a.y_min.min(b.y_min),
+        y_max: a.y_max.max(b.y_max),
+    })
+}
+
+// Przelicza przebieg do układu środka masy (położenia i prędkości)
+pub fn to_center_of_mass_frame(data: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    data.iter()
+        .map(|state| {
+            let (cx, cy, cvx, cvy) = center_of_mass(state);
+            let mut moved = state.clone();
+            for body in 0..3 {
+                moved[2 * body] -= cx;
+                moved[2 * body + 1] -= cy;
+                moved[6 + 2 * body] -= cvx;
+                moved[7 + 2 * body] -= cvy;
+            }
+            moved
+        })
+        .collect()
+}
+
+// Wyznacza kadr dla każdej klatki; `frames` to stany kolejnych klatek.
+// `aspect` to stosunek szerokości do wysokości obszaru wykresu.
+pub fn viewports(camera: Camera, data: &[Vec<f64>], frames: &[Sample], aspect: f64) -> Vec<Viewport> {
+    match camera {
+        // Dla układu środka masy dane są już przeliczone, więc kadr jest stały
+        Camera::Global | Camera::CenterOfMass => vec![global_viewport(data); frames.len()],
+        Camera::Fixed(viewport) => vec![viewport; frames.len()],
+        Camera::Follow(body, width) => {
+            // Domyślnie dwukrotność początkowego rozmiaru układu
+            let width = width.unwrap_or_else(|| 2.0 * bounding_box(&data[0]).extent());
+            frames
+                .iter()
+                .map(|frame| {
+                    let state = &frame.state;
+                    Viewport::centered(state[2 * body], state[2 * body + 1], width / 2.0, width / 2.0 / aspect)
+                })
+                .collect()
+        }
+        Camera::AutoZoom => {
+            let mut result = Vec::with_capacity(frames.len());
+            let mut current: Option<(f64, f64, f64)> = None; // środek i połowa szerokości
+            for frame in frames {
+                let bbox = bounding_box(&frame.state);
+                let (cx, cy) = ((bbox.x_min + bbox.x_max) / 2.0, (bbox.y_min + bbox.y_max) / 2.0);
+                let half_width = ((bbox.x_max - bbox.x_min) / 2.0)
+                    .max((bbox.y_max - bbox.y_min) / 2.0 * aspect)
+                    .max(1e-3)
+                    * MARGIN;
+                let (x, y, w) = match current {
+                    None => (cx, cy, half_width),
+                    Some((x, y, w)) => {
+                        let x = x + SMOOTHING * (cx - x);
+                        let y = y + SMOOTHING * (cy - y);
+                        // Oddalamy od razu, żeby żadne ciało nie wyszło poza kadr,
+                        // a przybliżamy stopniowo
+                        let needed = ((bbox.x_min - x).abs().max((bbox.x_max - x).abs()))
+                            .max((bbox.y_min - y).abs().max((bbox.y_max - y).abs()) * aspect)
+                            * MARGIN;
+                        let w = (w + SMOOTHING * (half_width - w)).max(needed);
+                        (x, y, w)
+                    }
+                };
+                current = Some((x, y, w));
+                result.push(Viewport::centered(x, y, w, w / aspect));
+            }
+            result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenario(json: &str) -> Result<Scenario, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    #[test]
+    fn parses_camera_specs() {
+        assert_eq!(Camera::parse("global"), Some(Camera::Global));
+        assert_eq!(Camera::parse("com"), Some(Camera::CenterOfMass));
+        assert_eq!(Camera::parse("zoom"), Some(Camera::AutoZoom));
+        assert_eq!(Camera::parse("follow:1"), Some(Camera::Follow(0, None)));
+        assert_eq!(Camera::parse("follow:3:2.5"), Some(Camera::Follow(2, Some(2.5))));
+        assert_eq!(
+            Camera::parse("fixed:-2, 2,-1,1"),
+            Some(Camera::Fixed(Viewport { x_min: -2.0, x_max: 2.0, y_min: -1.0, y_max: 1.0 }))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_camera_specs() {
+        for spec in [
+            "", "orbit", "global:1", "follow", "follow:0", "follow:4", "follow:x",
+            "follow:2:0", "follow:2:-1", "follow:2:1:1", "fixed", "fixed:1,2,3",
+            "fixed:2,1,0,1", "fixed:0,1,1,1", "fixed:0,1,a,2",
+        ] {
+            assert_eq!(Camera::parse(spec), None, "{:?}", spec);
+        }
+    }
+
+    #[test]
+    fn scenario_selects_camera() {
+        let viewport = Viewport { x_min: -2.0, x_max: 2.0, y_min: -1.5, y_max: 1.5 };
+        let fixed = r#"{"camera": "fixed", "viewport": {"x_min": -2, "x_max": 2, "y_min": -1.5, "y_max": 1.5}}"#;
+        assert_eq!(scenario(fixed).unwrap().camera(), Ok(Some(Camera::Fixed(viewport))));
+        let viewport_only = r#"{"viewport": {"x_min": -2, "x_max": 2, "y_min": -1.5, "y_max": 1.5}}"#;
+        assert_eq!(scenario(viewport_only).unwrap().camera(), Ok(Some(Camera::Fixed(viewport))));
+        assert_eq!(scenario(r#"{"camera": "follow:2:3.0"}"#).unwrap().camera(), Ok(Some(Camera::Follow(1, Some(3.0)))));
+        assert_eq!(scenario("{}").unwrap().camera(), Ok(None));
+    }
+
+    #[test]
+    fn rejects_invalid_scenarios() {
+        // Nieznane pola i brakujące granice kadru odrzuca już deserializacja
+        assert!(scenario(r#"{"camera": "com", "zoom": 2}"#).is_err());
+        assert!(scenario(r#"{"viewport": {"x_min": -2, "x_max": 2}}"#).is_err());
+        assert!(scenario(r#"{"camera": 3}"#).is_err());
+
+        let cases = [
+            r#"{"camera": "orbit"}"#,
+            r#"{"camera": "zoom", "viewport": {"x_min": -2, "x_max": 2, "y_min": -1, "y_max": 1}}"#,
+            r#"{"viewport": {"x_min": 2, "x_max": -2, "y_min": -1, "y_max": 1}}"#,
+        ];
+        for json in cases {
+            assert!(scenario(json).unwrap().camera().is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn auto_zoom_keeps_every_body_in_view() {
+        // Ciała krążą z różnymi częstotliwościami, a trzecie co jakiś czas
+        // ucieka daleko i wraca - kadr musi je złapać w tej samej klatce
+        let frames: Vec<Sample> = (0..600)
+            .map(|k| {
+                let t = k as f64 * 0.05;
+                let escape = if (k / 50) % 4 == 3 { 20.0 } else { 1.0 };
+                let state = vec![
+                    t.cos(), t.sin(),
+                    -2.0 * (1.3 * t).cos(), 0.5 * (1.3 * t).sin(),
+                    escape * (0.7 * t).sin(), -escape * (2.1 * t).cos(),
+                    0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+                ];
+                Sample { time: t, step: k, state }
+            })
+            .collect();
+        let data: Vec<Vec<f64>> = frames.iter().map(|f| f.state.clone()).collect();
+
+        for aspect in [0.5, 1.0, 16.0 / 9.0] {
+            let views = viewports(Camera::AutoZoom, &data, &frames, aspect);
+            assert_eq!(views.len(), frames.len());
+            for (k, (view, frame)) in views.iter().zip(&frames).enumerate() {
+                assert!(((view.x_max - view.x_min) / (view.y_max - view.y_min) - aspect).abs() < 1e-9);
+                for body in 0..3 {
+                    let (x, y) = (frame.state[2 * body], frame.state[2 * body + 1]);
+                    assert!(
+                        view.x_min <= x && x <= view.x_max && view.y_min <= y && y <= view.y_max,
+                        "proporcje {}, klatka {}, ciało {}: ({}, {}) poza {:?}", aspect, k, body + 1, x, y, view
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,18 +1,81 @@
 mod physics;
+mod output;
 mod visualization;
-mod gif;
+mod animation;
+mod trail;
+mod camera;
+mod sampling;
+mod overlay;
+mod watch;
+mod manifest;
+mod post_newtonian;
 
-use physics::{three_body, euler, rk4};
-// Remove unused import
-use visualization::{draw_method_comparison_grid, plot_energy_errors_grid};
-use gif::create_animation;
+use physics::{euler, energy_errors, time_points, Gravity, Method, Summation};
+use visualization::{draw_run_report, draw_trajectory_grid, plot_energy_errors_grid, Run};
+use output::PlotFormat;
+use animation::{create_animation, AnimationFormat, AnimationOptions};
+use trail::{TrailColor, TrailMode};
+use camera::{Camera, Scenario};
+use overlay::Overlays;
+use watch::{watch, WatchOptions};
+use manifest::{print_comparison, InitialConditions, Manifest, RunRecord};
+use post_newtonian::{precession_check, precession_initial_state, PrecessionCheck};
 use chrono::Local;
 use std::env;
+use std::path::Path;
+use std::time::Instant;
+
+// Wartość opcji podanej jako `--nazwa wartość`
+fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .map(|s| s.as_str())
+}
+
+// Czy podano flagę `--nazwa`
+fn has_flag(args: &[String], name: &str) -> bool {
+    args.iter().any(|a| a == name)
+}
+
+// Lista wartości rozdzielonych przecinkami
+fn parse_list<T>(spec: &str, parse: impl Fn(&str) -> Option<T>) -> Result<Vec<T>, String> {
+    spec.split(',')
+        .map(|item| parse(item.trim()).ok_or_else(|| format!("Niepoprawna wartość: {}", item)))
+        .collect()
+}
+
+// Końcowy względny błąd energii przebiegu
+fn final_energy_error(data: &[Vec<f64>], gravity: Gravity) -> f64 {
+    *energy_errors(data, gravity).last().unwrap()
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Parse command line arguments
     let args: Vec<String> = env::args().collect();
-    let mode = if args.len() > 1 { &args[1] } else { "plot" };  // Default to plot if no arg provided
+    let mode = match args.get(1) {
+        Some(arg) if !arg.starts_with("--") => arg.as_str(),
+        _ => "plot",  // Default to plot if no arg provided
+    };
+    
+    // Sumowanie z kompensacją: --summation naive|kahan|neumaier
+    let summation = match option_value(&args, "--summation") {
+        Some(name) => Summation::parse(name)
+            .ok_or_else(|| format!("Nieznany sposób sumowania: {}", name))?,
+        None => Summation::Naive,
+    };
+    
+    // Poprawki post-newtonowskie 1PN z prędkością światła c: --c C
+    // (w trybach plot, gif i watch; w trybie precession to parametr testu)
+    let speed_of_light = match option_value(&args, "--c") {
+        Some(value) => Some(value.parse::<f64>().ok().filter(|c| *c > 0.0)
+            .ok_or_else(|| format!("Niepoprawna prędkość światła: {}", value))?),
+        None => None,
+    };
+    let gravity = match speed_of_light {
+        Some(c) => Gravity::PostNewtonian(c),
+        None => Gravity::Newton,
+    };
     
     // Początkowe warunki: 3 ciała
     let y0 = vec![
@@ -26,61 +89,262 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let now = Local::now();
     let timestamp = now.format("%Y%m%d_%H%M%S").to_string();
+    // Manifest przebiegu zapisywany obok wyników
+    let mut manifest = Manifest::new(mode, &args, now.to_rfc3339(), &y0);
+    let manifest_filename = format!("manifest_{}.json", timestamp);
 
     match mode {
-        "gif" => {
-            println!("Tryb animacji GIF");
+        "gif" | "animation" => {
+            println!("Tryb animacji");
             // For GIF we only need one simulation with reasonable parameters
             let dt = 0.001;
             let steps = 100000;
             
-            println!("Running simulation with dt = {}", dt);
-            let euler_result = euler(three_body, y0.clone(), 0.0, dt, steps);
+            println!("Running simulation with dt = {} ({})", dt, summation.label());
+            if gravity != Gravity::Newton {
+                println!("Model grawitacji: {}", gravity.label());
+            }
+            let start = Instant::now();
+            let euler_result = euler(|y: &[f64], t| gravity.derivative(y, t), y0.clone(), 0.0, dt, steps, summation);
+            manifest.runs.push(RunRecord::new(Method::Euler, summation, gravity, dt, &euler_result, start.elapsed()));
             
             // Create animation
-            let gif_filename = format!("three_body_animation_{}.gif", timestamp);
-            println!("Generowanie animacji GIF...");
-            create_animation(&euler_result, &gif_filename, 20)?;
+            let mut options = AnimationOptions { gravity, ..AnimationOptions::default() };
+            // Wynik: --output plik.gif|plik.png|katalog, --format gif|apng|png-seq
+            let output = option_value(&args, "--output");
+            options.format = match (option_value(&args, "--format"), output) {
+                (Some(name), _) => AnimationFormat::parse(name)
+                    .ok_or_else(|| format!("Nieznany format animacji: {}", name))?,
+                (None, Some(path)) => AnimationFormat::from_path(path)
+                    .ok_or_else(|| format!("Nie można ustalić formatu animacji z nazwy: {}", path))?,
+                (None, None) => AnimationFormat::Gif,
+            };
+            let animation_filename = match output {
+                Some(path) => path.to_string(),
+                None => options.format.default_output(&timestamp),
+            };
+            // Ślady: --trail full|fixed:N|fade:N, --trail-color body|speed|energy
+            if let Some(spec) = option_value(&args, "--trail") {
+                options.trail_mode = TrailMode::parse(spec)
+                    .ok_or_else(|| format!("Nieznany tryb śladu: {}", spec))?;
+            }
+            if let Some(name) = option_value(&args, "--trail-color") {
+                options.trail_color = TrailColor::parse(name)
+                    .ok_or_else(|| format!("Nieznane kolorowanie śladu: {}", name))?;
+            }
+            // Kamera: --scenario plik.json (tryb i kadr, patrz camera::Scenario)
+            // albo --camera global|com|follow:N[:szerokość]|zoom|fixed:xmin,xmax,ymin,ymax;
+            // --camera ma pierwszeństwo przed scenariuszem
+            if let Some(path) = option_value(&args, "--scenario")
+                && let Some(camera) = Scenario::read(path)?.camera()? {
+                options.camera = camera;
+            }
+            if let Some(spec) = option_value(&args, "--camera") {
+                options.camera = Camera::parse(spec)
+                    .ok_or_else(|| format!("Nieznany tryb kamery: {}", spec))?;
+            }
+            // Tempo: --fps N, --time-scale T (jednostek czasu fizycznego na sekundę animacji)
+            if let Some(value) = option_value(&args, "--fps") {
+                options.fps = value.parse::<f64>().ok().filter(|v| *v > 0.0)
+                    .ok_or_else(|| format!("Niepoprawna liczba klatek na sekundę: {}", value))?;
+            }
+            if let Some(value) = option_value(&args, "--time-scale") {
+                options.time_per_second = value.parse::<f64>().ok().filter(|v| *v > 0.0)
+                    .ok_or_else(|| format!("Niepoprawne tempo animacji: {}", value))?;
+            }
+            // Nakładki: --overlay velocity,invariants,inset,clock albo all
+            if let Some(spec) = option_value(&args, "--overlay") {
+                options.overlays = Overlays::parse(spec)
+                    .ok_or_else(|| format!("Nieznana nakładka: {}", spec))?;
+            }
+            let times = time_points(0.0, dt, euler_result.len());
+            println!("Generowanie animacji...");
+            create_animation(&euler_result, &times, &animation_filename, &options)?;
+            manifest.outputs.push(animation_filename.clone());
+            manifest.write(&manifest_filename)?;
             
             println!("Animacja zakończona. Wygenerowano:");
-            println!("- {}", gif_filename);
+            println!("- {}", animation_filename);
+            println!("- {}", manifest_filename);
+        },
+        "compare" => {
+            // Porównanie dwóch manifestów: compare a.json b.json
+            let (Some(first), Some(second)) = (args.get(2), args.get(3)) else {
+                return Err("Użycie: compare <manifest1.json> <manifest2.json>".into());
+            };
+            print_comparison(&Manifest::read(first)?, &Manifest::read(second)?);
+        },
+        "precession" => {
+            // Test precesji peryhelium w modelu 1PN: --c, --eccentricity, --orbits, --tolerance
+            let mut check = PrecessionCheck::default();
+            if let Some(c) = speed_of_light {
+                check.c = c;
+            }
+            if let Some(value) = option_value(&args, "--eccentricity") {
+                check.eccentricity = value.parse::<f64>().ok().filter(|e| (0.0..1.0).contains(e))
+                    .ok_or_else(|| format!("Niepoprawny mimośród: {}", value))?;
+            }
+            if let Some(value) = option_value(&args, "--orbits") {
+                check.orbits = value.parse::<usize>().ok().filter(|n| *n >= 2)
+                    .ok_or_else(|| format!("Niepoprawna liczba obiegów: {}", value))?;
+            }
+            if let Some(value) = option_value(&args, "--tolerance") {
+                check.tolerance = value.parse::<f64>().ok().filter(|t| *t > 0.0)
+                    .ok_or_else(|| format!("Niepoprawna tolerancja: {}", value))?;
+            }
+            let record = precession_check(&check)?;
+            manifest.initial_conditions = InitialConditions {
+                g: physics::G,
+                masses: check.masses.to_vec(),
+                state: precession_initial_state(&check),
+            };
+            let passed = record.passed();
+            let relative_error = record.relative_error;
+            manifest.precession = Some(record);
+            manifest.write(&manifest_filename)?;
+            println!("Manifest zapisany jako {}", manifest_filename);
+            if !passed {
+                return Err(format!("Precesja niezgodna z wartością analityczną (błąd {:.3e})", relative_error).into());
+            }
+            println!("OK");
+        },
+        "watch" => {
+            // Podgląd na żywo w terminalu: --method euler|rk4, --dt, --fps,
+            // --time-scale T oraz --trail-length N (liczba kroków w śladzie)
+            let mut options = WatchOptions { summation, gravity, ..WatchOptions::default() };
+            if let Some(name) = option_value(&args, "--method") {
+                options.method = Method::parse(name)
+                    .ok_or_else(|| format!("Nieznana metoda: {}", name))?;
+            }
+            if let Some(value) = option_value(&args, "--dt") {
+                options.dt = value.parse::<f64>().ok().filter(|v| *v > 0.0)
+                    .ok_or_else(|| format!("Niepoprawny krok czasowy: {}", value))?;
+            }
+            if let Some(value) = option_value(&args, "--fps") {
+                options.fps = value.parse::<f64>().ok().filter(|v| *v > 0.0)
+                    .ok_or_else(|| format!("Niepoprawna liczba klatek na sekundę: {}", value))?;
+            }
+            if let Some(value) = option_value(&args, "--time-scale") {
+                options.time_per_second = value.parse::<f64>().ok().filter(|v| *v > 0.0)
+                    .ok_or_else(|| format!("Niepoprawne tempo animacji: {}", value))?;
+            }
+            if let Some(value) = option_value(&args, "--trail-length") {
+                options.trail_length = value.parse::<usize>()
+                    .map_err(|_| format!("Niepoprawna długość śladu: {}", value))?;
+            }
+            watch(y0, &options)?;
         },
         _ => {  // Default to plots for any other input
             println!("Tryb statycznych wykresów");
-            // Define 4 different time steps - from coarse to fine
-            let dt_values = [0.001, 0.0001, 0.00001, 0.000001];
-            let steps_values = [10000, 100000, 1000000, 10000000];
+            // Metody i kroki czasowe do porównania: --methods euler,rk4, --dt 0.001,0.0001,...
+            // oraz długość symulacji --duration T. Domyślnie 4 kroki - od grubego do drobnego.
+            let methods = match option_value(&args, "--methods") {
+                Some(spec) => parse_list(spec, Method::parse)?,
+                None => Method::ALL.to_vec(),
+            };
+            let dt_values = match option_value(&args, "--dt") {
+                Some(spec) => parse_list(spec, |v| v.parse::<f64>().ok().filter(|dt| *dt > 0.0))?,
+                None => vec![0.001, 0.0001, 0.00001, 0.000001],
+            };
+            let duration = match option_value(&args, "--duration") {
+                Some(value) => value.parse::<f64>().ok().filter(|t| *t > 0.0)
+                    .ok_or_else(|| format!("Niepoprawny czas symulacji: {}", value))?,
+                None => 10.0,
+            };
+            // Wspólne zakresy osi na wszystkich panelach: --shared-axes
+            let shared_axes = has_flag(&args, "--shared-axes");
+            // Pliki wykresów: --output katalog/nazwa.png|svg|pdf daje
+            // katalog/nazwa_<wykres>.<rozszerzenie>, a backend wynika
+            // z rozszerzenia; bez tej opcji <wykres>_<znacznik czasu>.png
+            let output = match option_value(&args, "--output") {
+                Some(path) => Some((Path::new(path).with_extension("").display().to_string(), PlotFormat::from_filename(path)?)),
+                None => None,
+            };
+            let plot_filename = |kind: &str| match &output {
+                Some((stem, format)) => format!("{}_{}.{}", stem, kind, format.extension()),
+                None => format!("{}_{}.png", kind, timestamp),
+            };
+            let manifest_filename = match &output {
+                Some((stem, _)) => format!("{}_manifest.json", stem),
+                None => manifest_filename,
+            };
             
-            let mut euler_results = Vec::new();
-            let mut rk4_results = Vec::new();
+            let mut runs = Vec::new();
+            let force = |y: &[f64], t: f64| gravity.derivative(y, t);
+            if gravity != Gravity::Newton {
+                println!("Model grawitacji: {}", gravity.label());
+            }
             
             // Run simulations for each dt
-            for (i, &dt) in dt_values.iter().enumerate() {
+            for &dt in &dt_values {
                 println!("Running simulations with dt = {}", dt);
-                let steps = steps_values[i];
-                
-                let euler_result = euler(three_body, y0.clone(), 0.0, dt, steps);
-                let rk4_result = rk4(three_body, y0.clone(), 0.0, dt, steps);
+                let steps = (duration / dt).round() as usize;
                 
-                euler_results.push(euler_result);
-                rk4_results.push(rk4_result);
+                for &method in &methods {
+                    let start = Instant::now();
+                    let data = method.integrate(force, y0.clone(), 0.0, dt, steps, Summation::Naive);
+                    manifest.runs.push(RunRecord::new(method, Summation::Naive, gravity, dt, &data, start.elapsed()));
+                    println!("  Błąd energii na końcu: {} {:.3e}", method.name(), final_energy_error(&data, gravity));
+                    let times = time_points(0.0, dt, data.len());
+                    runs.push(Run { method, dt, summation: Summation::Naive, gravity, times, data });
+                    
+                    // Ten sam przebieg z kompensacją, żeby pokazać różnicę w raporcie energii
+                    if summation != Summation::Naive {
+                        let start = Instant::now();
+                        let data = method.integrate(force, y0.clone(), 0.0, dt, steps, summation);
+                        manifest.runs.push(RunRecord::new(method, summation, gravity, dt, &data, start.elapsed()));
+                        println!("  Błąd energii na końcu: {} ({}) {:.3e}", method.name(), summation.label(),
+                                 final_energy_error(&data, gravity));
+                        let times = time_points(0.0, dt, data.len());
+                        runs.push(Run { method, dt, summation, gravity, times, data });
+                    }
+                }
             }
             
-            // Create the comparison grids
-            let euler_filename = format!("euler_grid_comparison_{}.png", timestamp);
-            let rk4_filename = format!("rk4_grid_comparison_{}.png", timestamp);
-            let energy_filename = format!("energy_error_grid_{}.png", timestamp);
-
-            draw_method_comparison_grid("Metoda Eulera", &euler_results, &dt_values, &euler_filename)?;
-            draw_method_comparison_grid("Metoda RK4", &rk4_results, &dt_values, &rk4_filename)?;
+            let mut outputs = Vec::new();
+            let naive_runs: Vec<&Run> = runs.iter().filter(|r| r.summation == Summation::Naive).collect();
+            
+            // Siatka torów dla każdej metody osobno
+            for &method in &methods {
+                let method_runs: Vec<&Run> = naive_runs.iter().copied().filter(|r| r.method == method).collect();
+                let filename = plot_filename(&format!("{}_grid_comparison", method.file_stem()));
+                draw_trajectory_grid(&method_runs, None, shared_axes, &filename)?;
+                outputs.push(filename);
+            }
+            
+            // Wszystkie metody obok siebie: wiersz na metodę, kolumna na krok czasowy
+            if methods.len() > 1 {
+                let by_method: Vec<&Run> = methods.iter()
+                    .flat_map(|&m| naive_runs.iter().copied().filter(move |r| r.method == m))
+                    .collect();
+                let filename = plot_filename("methods_grid_comparison");
+                draw_trajectory_grid(&by_method, Some((methods.len(), dt_values.len())), shared_axes, &filename)?;
+                outputs.push(filename);
+            }
             
             // Create energy comparison grid
-            plot_energy_errors_grid(&euler_results, &rk4_results, &dt_values, &energy_filename)?;
+            let energy_filename = plot_filename("energy_error_grid");
+            let all_runs: Vec<&Run> = runs.iter().collect();
+            plot_energy_errors_grid(&all_runs, shared_axes, &energy_filename)?;
+            outputs.push(energy_filename);
+
+            // Raport diagnostyczny dla każdego przebiegu: --report
+            if has_flag(&args, "--report") {
+                for run in &naive_runs {
+                    let filename = plot_filename(&format!("report_{}_dt{}", run.method.file_stem(), run.dt));
+                    draw_run_report(run, &filename)?;
+                    outputs.push(filename);
+                }
+            }
+
+            manifest.outputs = outputs.clone();
+            manifest.write(&manifest_filename)?;
+            outputs.push(manifest_filename);
 
             println!("Symulacja zakończona. Wygenerowano:");
-            println!("- {}", euler_filename);
-            println!("- {}", rk4_filename);
-            println!("- {}", energy_filename);
+            for filename in &outputs {
+                println!("- {}", filename);
+            }
         }
     }
     
@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::time::Duration;
+use crate::physics::{angular_momentum, linear_momentum, Gravity, Method, Summation, G, MASSES};
+
+// Manifest zapisywany obok wyników: wszystko, co potrzebne, żeby po czasie
+// ustalić, czym i z jakimi parametrami powstały wykresy
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InitialConditions {
+    pub g: f64,
+    pub masses: Vec<f64>,
+    pub state: Vec<f64>, // x1, y1, ..., vx1, vy1, ...
+}
+
+fn newtonian() -> String {
+    Gravity::Newton.label()
+}
+
+// Opis jednego przebiegu integratora
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RunRecord {
+    pub method: String,
+    pub summation: String,
+    // Model grawitacji; w starszych manifestach brak pola oznacza Newtona
+    #[serde(default = "newtonian")]
+    pub gravity: String,
+    pub dt: f64,
+    pub steps: usize,
+    pub t_end: f64,
+    pub wall_time_s: f64,
+    // Końcowe błędy niezmienników: energia i moment pędu względnie,
+    // pęd bezwzględnie (początkowo zwykle zerowy)
+    pub energy_error: f64,
+    pub angular_momentum_error: f64,
+    pub linear_momentum_error: f64,
+}
+
+impl RunRecord {
+    pub fn new(method: Method, summation: Summation, gravity: Gravity, dt: f64, data: &[Vec<f64>], wall_time: Duration) -> Self {
+        let (first, last) = (&data[0], &data[data.len() - 1]);
+        let e0 = gravity.energy(first);
+        let l0 = angular_momentum(first);
+        let l_scale = if l0.abs() > 1e-12 { l0.abs() } else { 1.0 };
+        let (px0, py0) = linear_momentum(first);
+        let (px, py) = linear_momentum(last);
+        let steps = data.len() - 1;
+
+        RunRecord {
+            method: method.file_stem().to_string(),
+            summation: summation.name().to_string(),
+            gravity: gravity.label(),
+            dt,
+            steps,
+            t_end: steps as f64 * dt,
+            wall_time_s: wall_time.as_secs_f64(),
+            energy_error: (gravity.energy(last) - e0).abs() / e0.abs(),
+            angular_momentum_error: (angular_momentum(last) - l0).abs() / l_scale,
+            linear_momentum_error: (px - px0).hypot(py - py0),
+        }
+    }
+
+    fn key(&self) -> (String, String, String, f64) {
+        (self.method.clone(), self.summation.clone(), self.gravity.clone(), self.dt)
+    }
+
+    fn describe(&self) -> String {
+        format!("{} ({}, {}) dt = {}", self.method, self.summation, self.gravity, self.dt)
+    }
+}
+
+// Wynik testu precesji peryhelium (tryb precession)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PrecessionRecord {
+    pub masses: [f64; 2],
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub c: f64,
+    pub method: String,
+    pub dt: f64,
+    pub steps: usize,
+    pub wall_time_s: f64,
+    pub periastra: usize,
+    // Tempo precesji w radianach na obieg
+    pub measured_rate: f64,
+    pub analytic_rate: f64,
+    pub relative_error: f64,
+    pub tolerance: f64,
+    // Maksymalny względny błąd energii 1PN w przebiegu
+    pub energy_error: f64,
+}
+
+impl PrecessionRecord {
+    pub fn passed(&self) -> bool {
+        self.relative_error <= self.tolerance
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Manifest {
+    pub crate_version: String,
+    pub git_revision: String,
+    pub created: String,
+    pub mode: String,
+    pub command_line: Vec<String>,
+    pub initial_conditions: InitialConditions,
+    pub runs: Vec<RunRecord>,
+    // Tylko w trybie precession
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precession: Option<PrecessionRecord>,
+    pub outputs: Vec<String>,
+}
+
+impl Manifest {
+    pub fn new(mode: &str, command_line: &[String], created: String, y0: &[f64]) -> Self {
+        Manifest {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            git_revision: env!("GIT_REVISION").to_string(),
+            created,
+            mode: mode.to_string(),
+            command_line: command_line.to_vec(),
+            initial_conditions: InitialConditions { g: G, masses: MASSES.to_vec(), state: y0.to_vec() },
+            runs: Vec::new(),
+            precession: None,
+            outputs: Vec::new(),
+        }
+    }
+
+    pub fn write(&self, filename: &str) -> Result<(), Box<dyn Error>> {
+        std::fs::write(filename, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn read(filename: &str) -> Result<Manifest, Box<dyn Error>> {
+        let text = std::fs::read_to_string(filename)?;
+        serde_json::from_str(&text).map_err(|e| format!("Niepoprawny manifest {}: {}", filename, e).into())
+    }
+}
+
+// Zmiana wartości w postaci "a -> b" albo sama wartość, gdy się nie zmieniła
+fn change<T: PartialEq + std::fmt::Display>(a: T, b: T) -> String {
+    if a == b { format!("{}", a) } else { format!("{} -> {}", a, b) }
+}
+
+fn ratio(a: f64, b: f64) -> String {
+    if a > 0.0 { format!(" (x{:.3})", b / a) } else { String::new() }
+}
+
+fn show(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => "-".to_string(),
+        other => other.to_string(),
+    }
+}
+
+// Wypisuje różniące się pola dwóch rekordów (przez ich postać JSON, więc
+// obejmuje każde pole, także dodane później); przy liczbach także iloraz
+fn print_field_changes<T: Serialize>(indent: &str, a: &T, b: &T) {
+    let (Ok(Value::Object(a)), Ok(Value::Object(b))) = (serde_json::to_value(a), serde_json::to_value(b)) else {
+        return;
+    };
+    let names = a.keys().chain(b.keys().filter(|name| !a.contains_key(*name)));
+    let mut changed = false;
+    for name in names {
+        let (va, vb) = (a.get(name).unwrap_or(&Value::Null), b.get(name).unwrap_or(&Value::Null));
+        if va == vb {
+            continue;
+        }
+        let suffix = match (va.as_f64(), vb.as_f64()) {
+            (Some(x), Some(y)) => ratio(x, y),
+            _ => String::new(),
+        };
+        println!("{}{:<24} {} -> {}{}", indent, format!("{}:", name), show(va), show(vb), suffix);
+        changed = true;
+    }
+    if !changed {
+        println!("{}bez zmian", indent);
+    }
+}
+
+// Wypisuje różnice między dwoma manifestami
+pub fn print_comparison(a: &Manifest, b: &Manifest) {
+    let command = |m: &Manifest| m.command_line.iter().skip(1).cloned().collect::<Vec<_>>().join(" ");
+    println!("Wersja:    {}", change(&a.crate_version, &b.crate_version));
+    println!("Rewizja:   {}", change(&a.git_revision, &b.git_revision));
+    println!("Utworzono: {}", change(&a.created, &b.created));
+    println!("Tryb:      {}", change(&a.mode, &b.mode));
+    println!("Polecenie: {}", change(command(a), command(b)));
+
+    println!("Warunki początkowe:");
+    print_field_changes("  ", &a.initial_conditions, &b.initial_conditions);
+
+    if !a.runs.is_empty() || !b.runs.is_empty() {
+        println!("Przebiegi:");
+    }
+    for run in &a.runs {
+        match b.runs.iter().find(|other| other.key() == run.key()) {
+            Some(other) => {
+                println!("  {}", run.describe());
+                print_field_changes("    ", run, other);
+            }
+            None => {
+                println!("  {}: tylko w pierwszym manifeście", run.describe());
+            }
+        }
+    }
+    for run in b.runs.iter().filter(|run| !a.runs.iter().any(|other| other.key() == run.key())) {
+        println!("  {}: tylko w drugim manifeście", run.describe());
+    }
+
+    match (&a.precession, &b.precession) {
+        (Some(pa), Some(pb)) => {
+            println!("Test precesji:");
+            print_field_changes("  ", pa, pb);
+        }
+        (Some(_), None) => println!("Test precesji: tylko w pierwszym manifeście"),
+        (None, Some(_)) => println!("Test precesji: tylko w drugim manifeście"),
+        (None, None) => {}
+    }
+
+    println!("Pliki wynikowe:");
+    let mut same = true;
+    for output in a.outputs.iter().filter(|o| !b.outputs.contains(o)) {
+        println!("  - {}", output);
+        same = false;
+    }
+    for output in b.outputs.iter().filter(|o| !a.outputs.contains(o)) {
+        println!("  + {}", output);
+        same = false;
+    }
+    if same {
+        println!("  bez zmian ({})", a.outputs.len());
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::Path;
+use svg2pdf::usvg::{self, fontdb, PostProcessingSteps, TreeParsing, TreePostProc};
+
+// Format pliku z wykresem, wybierany na podstawie rozszerzenia nazwy
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlotFormat {
+    Png,
+    Svg,
+    Pdf,
+}
+
+impl PlotFormat {
+    pub fn parse(name: &str) -> Option<PlotFormat> {
+        match name.to_lowercase().as_str() {
+            "png" => Some(PlotFormat::Png),
+            "svg" => Some(PlotFormat::Svg),
+            "pdf" => Some(PlotFormat::Pdf),
+            _ => None,
+        }
+    }
+
+    pub fn from_filename(filename: &str) -> Result<PlotFormat, String> {
+        Path::new(filename)
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(PlotFormat::parse)
+            .ok_or_else(|| format!("Nieobsługiwany format wykresu: {}", filename))
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PlotFormat::Png => "png",
+            PlotFormat::Svg => "svg",
+            PlotFormat::Pdf => "pdf",
+        }
+    }
+}
+
+// Zamienia wykres SVG na PDF. Tekst jest zamieniany na krzywe,
+// więc potrzebne są czcionki systemowe.
+pub fn svg_to_pdf(svg: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut tree = usvg::Tree::from_str(svg, &usvg::Options::default())?;
+    let mut fonts = fontdb::Database::new();
+    fonts.load_system_fonts();
+    // fontdb domyślnie szuka Ariala; jeśli go nie ma, bierzemy inną czcionkę
+    // bezszeryfową, a w ostateczności pierwszą dostępną
+    let sans_serif = fontdb::Query { families: &[fontdb::Family::SansSerif], ..Default::default() };
+    if fonts.query(&sans_serif).is_none() {
+        let families: Vec<String> = fonts.faces()
+            .filter_map(|face| face.families.first().map(|(name, _)| name.clone()))
+            .collect();
+        let fallback = families.iter()
+            .find(|name| name.contains("Sans") && !name.contains("Mono"))
+            .or(families.first());
+        if let Some(family) = fallback.cloned() {
+            fonts.set_sans_serif_family(family);
+        }
+    }
+    tree.postprocess(PostProcessingSteps::default(), &fonts);
+    Ok(svg2pdf::convert_tree(&tree, svg2pdf::Options::default()))
+}
+
+// Zapisuje wykres do pliku, dobierając backend do rozszerzenia nazwy.
+// `$draw` jest wywoływane z obszarem rysowania danego backendu - makro
+// rozwija je osobno dla każdego z nich, więc może wołać funkcje generyczne.
+macro_rules! save_plot {
+    ($filename:expr, $size:expr, $draw:expr) => {{
+        let filename: &str = $filename;
+        match $crate::output::PlotFormat::from_filename(filename)? {
+            $crate::output::PlotFormat::Png => {
+                let root = BitMapBackend::new(filename, $size).into_drawing_area();
+                ($draw)(&root)?;
+                root.present()?;
+            }
+            $crate::output::PlotFormat::Svg => {
+                let root = SVGBackend::new(filename, $size).into_drawing_area();
+                ($draw)(&root)?;
+                root.present()?;
+            }
+            $crate::output::PlotFormat::Pdf => {
+                let mut svg = String::new();
+                {
+                    let root = SVGBackend::with_string(&mut svg, $size).into_drawing_area();
+                    ($draw)(&root)?;
+                    root.present()?;
+                }
+                std::fs::write(filename, $crate::output::svg_to_pdf(&svg)?)?;
+            }
+        }
+        Ok(())
+    }};
+}
+
+pub(crate) use save_plot;
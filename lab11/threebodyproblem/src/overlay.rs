@@ -0,0 +1,176 @@
+use plotters::coord::types::RangedCoordf64;
+use plotters::prelude::*;
+use crate::physics::{angular_momentum, Gravity};
+use crate::sampling::Sample;
+
+const ARROW_LENGTH: f64 = 60.0; // długość strzałki najszybszego ciała w pikselach
+const INSET_POINTS: usize = 200; // maksymalna liczba punktów wykresu w okienku
+
+// Elementy rysowane na klatkach animacji
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Overlays {
+    pub velocity: bool,   // strzałki prędkości ciał
+    pub invariants: bool, // bieżący błąd energii i momentu pędu
+    pub inset: bool,      // wykres błędu energii do bieżącej chwili
+    pub clock: bool,      // zegar czasu fizycznego
+}
+
+impl Overlays {
+    // Lista rozdzielona przecinkami: velocity, invariants, inset, clock albo "all"
+    pub fn parse(spec: &str) -> Option<Overlays> {
+        let mut overlays = Overlays::default();
+        for name in spec.split(',') {
+            match name.trim() {
+                "velocity" => overlays.velocity = true,
+                "invariants" => overlays.invariants = true,
+                "inset" => overlays.inset = true,
+                "clock" => overlays.clock = true,
+                "all" => {
+                    overlays = Overlays { velocity: true, invariants: true, inset: true, clock: true }
+                }
+                _ => return None,
+            }
+        }
+        Some(overlays)
+    }
+}
+
+// Wielkości potrzebne do nakładek, policzone raz dla wszystkich klatek
+pub struct OverlayData {
+    overlays: Overlays,
+    times: Vec<f64>,
+    energy_errors: Vec<f64>,
+    momentum_errors: Vec<f64>,
+    max_speed: f64,
+}
+
+impl OverlayData {
+    pub fn new(overlays: Overlays, gravity: Gravity, initial: &[f64], samples: &[Sample]) -> Self {
+        let e0 = gravity.energy(initial);
+        let l0 = angular_momentum(initial);
+        // Gdy początkowy moment pędu jest zerowy, pokazujemy błąd bezwzględny
+        let l_scale = if l0.abs() > 1e-12 { l0.abs() } else { 1.0 };
+
+        let times = samples.iter().map(|s| s.time).collect();
+        let energy_errors = samples.iter()
+            .map(|s| (gravity.energy(&s.state) - e0).abs() / e0.abs())
+            .collect();
+        let momentum_errors = samples.iter()
+            .map(|s| (angular_momentum(&s.state) - l0).abs() / l_scale)
+            .collect();
+        let max_speed = samples.iter()
+            .flat_map(|s| (0..3).map(move |b| s.state[6 + 2 * b].hypot(s.state[7 + 2 * b])))
+            .fold(0.0, f64::max);
+
+        OverlayData { overlays, times, energy_errors, momentum_errors, max_speed }
+    }
+
+    pub fn draw<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, plotters::coord::Shift>,
+        chart: &ChartContext<DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+        sample: &Sample,
+        frame: usize,
+        colors: &[RGBColor; 3],
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        let style = ("sans-serif", 15).into_font().color(&BLACK);
+        let (width, _) = root.dim_in_pixel();
+
+        if self.overlays.velocity && self.max_speed > 0.0 {
+            for (body, color) in colors.iter().enumerate() {
+                let (vx, vy) = (sample.state[6 + 2 * body], sample.state[7 + 2 * body]);
+                let (px, py) = chart.backend_coord(&(sample.state[2 * body], sample.state[2 * body + 1]));
+                // Strzałka w pikselach, oś y ekranu jest skierowana w dół
+                let scale = ARROW_LENGTH / self.max_speed;
+                let (dx, dy) = (vx * scale, -vy * scale);
+                let length = dx.hypot(dy);
+                if length < 1.0 {
+                    continue;
+                }
+                let tip = (px + dx.round() as i32, py + dy.round() as i32);
+                root.draw(&PathElement::new(vec![(px, py), tip], color.stroke_width(2)))?;
+                // Grot strzałki
+                let (ux, uy) = (dx / length, dy / length);
+                let head = 8.0_f64.min(length / 2.0);
+                for side in [-1.0, 1.0] {
+                    let (hx, hy) = (-ux * head - side * uy * head / 2.0, -uy * head + side * ux * head / 2.0);
+                    let end = (tip.0 + hx.round() as i32, tip.1 + hy.round() as i32);
+                    root.draw(&PathElement::new(vec![tip, end], color.stroke_width(2)))?;
+                }
+            }
+        }
+
+        if self.overlays.clock {
+            root.draw(&Text::new(
+                format!("t = {:.3}", sample.time),
+                (70, 40),
+                ("sans-serif", 22).into_font().color(&BLACK),
+            ))?;
+        }
+
+        if self.overlays.invariants {
+            root.draw(&Text::new(
+                format!("ΔE/E = {:.3e}", self.energy_errors[frame]),
+                (70, 65),
+                style.clone(),
+            ))?;
+            root.draw(&Text::new(
+                format!("ΔL/L = {:.3e}", self.momentum_errors[frame]),
+                (70, 82),
+                style.clone(),
+            ))?;
+        }
+
+        if self.overlays.inset {
+            self.draw_inset(root, frame, width)?;
+        }
+
+        Ok(())
+    }
+
+    // Mały wykres błędu energii w prawym górnym rogu
+    fn draw_inset<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, plotters::coord::Shift>,
+        frame: usize,
+        width: u32,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        let area = root.clone().shrink((width as i32 - 250, 45), (220, 130));
+        area.fill(&WHITE.mix(0.85))?;
+        area.draw(&Rectangle::new([(0, 0), (219, 129)], BLACK))?;
+
+        let history = &self.energy_errors[..=frame];
+        let min_error = history.iter().cloned().fold(f64::MAX, f64::min).max(1e-16);
+        let max_error = history.iter().cloned().fold(0.0, f64::max).max(min_error * 10.0);
+
+        let mut chart = ChartBuilder::on(&area)
+            .caption("Błąd energii", ("sans-serif", 13))
+            .margin(5)
+            .x_label_area_size(15)
+            .y_label_area_size(40)
+            .build_cartesian_2d(
+                self.times[0]..self.times[frame].max(self.times[0] + 1e-9),
+                (min_error..max_error).log_scale(),
+            )?;
+
+        chart.configure_mesh()
+            .disable_mesh()
+            .x_labels(3)
+            .y_labels(3)
+            .label_style(("sans-serif", 10))
+            .y_label_formatter(&|v| format!("{:.0e}", v))
+            .draw()?;
+
+        // Co najwyżej INSET_POINTS punktów, żeby koszt klatki nie rósł z czasem
+        let stride = (history.len() / INSET_POINTS).max(1);
+        chart.draw_series(LineSeries::new(
+            history.iter()
+                .enumerate()
+                .step_by(stride)
+                .chain(std::iter::once((frame, &history[frame])))
+                .map(|(i, &e)| (self.times[i], e.max(min_error))),
+            &RED,
+        ))?;
+        Ok(())
+    }
+}
@@ -1,10 +1,11 @@
+use crate::post_newtonian;
+
 pub const G: f64 = 1.0; // Stała grawitacji
+pub const MASSES: [f64; 3] = [1.0, 1.0, 1.0]; // Masy ciał
 
 // Funkcja opisująca dynamikę układu 3 ciał w 2D
-pub fn three_body(y: &Vec<f64>, _t: f64) -> Vec<f64> {
-    let m1 = 1.0;
-    let m2 = 1.0;
-    let m3 = 1.0;
+pub fn three_body(y: &[f64], _t: f64) -> Vec<f64> {
+    let [m1, m2, m3] = MASSES;
 
     // pozycje
     let (x1, y1, x2, y2, x3, y3) = (y[0], y[1], y[2], y[3], y[4], y[5]);
@@ -37,91 +38,405 @@ pub fn three_body(y: &Vec<f64>, _t: f64) -> Vec<f64> {
     ]
 }
 
-// Metoda Eulera
-pub fn euler<F>(f: F, y0: Vec<f64>, t0: f64, dt: f64, steps: usize) -> Vec<Vec<f64>>
+// Sposób akumulacji stanu i czasu w integratorach
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Summation {
+    Naive,    // zwykłe y += dt * dy
+    Kahan,    // sumowanie z kompensacją Kahana
+    Neumaier, // ulepszona kompensacja Neumaiera (działa też gdy przyrost > suma)
+}
+
+impl Summation {
+    pub fn parse(name: &str) -> Option<Summation> {
+        match name.to_lowercase().as_str() {
+            "naive" | "none" => Some(Summation::Naive),
+            "kahan" => Some(Summation::Kahan),
+            "neumaier" => Some(Summation::Neumaier),
+            _ => None,
+        }
+    }
+
+    // Nazwa akceptowana przez `parse`, używana w manifestach
+    pub fn name(&self) -> &'static str {
+        match self {
+            Summation::Naive => "naive",
+            Summation::Kahan => "kahan",
+            Summation::Neumaier => "neumaier",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Summation::Naive => "bez kompensacji",
+            Summation::Kahan => "Kahan",
+            Summation::Neumaier => "Neumaier",
+        }
+    }
+}
+
+// Pojedyncza akumulowana wartość wraz z poprawką utraconych bitów
+#[derive(Clone, Copy)]
+struct Accumulator {
+    sum: f64,
+    comp: f64,
+}
+
+impl Accumulator {
+    fn new(value: f64) -> Self {
+        Accumulator { sum: value, comp: 0.0 }
+    }
+
+    fn add(&mut self, x: f64, mode: Summation) {
+        match mode {
+            Summation::Naive => self.sum += x,
+            Summation::Kahan => {
+                let y = x - self.comp;
+                let t = self.sum + y;
+                self.comp = (t - self.sum) - y;
+                self.sum = t;
+            }
+            Summation::Neumaier => {
+                let t = self.sum + x;
+                if self.sum.abs() >= x.abs() {
+                    self.comp += (self.sum - t) + x;
+                } else {
+                    self.comp += (x - t) + self.sum;
+                }
+                self.sum = t;
+            }
+        }
+    }
+
+    fn value(&self, mode: Summation) -> f64 {
+        match mode {
+            // W metodzie Kahana poprawka jest wliczana przy kolejnym dodawaniu
+            Summation::Naive | Summation::Kahan => self.sum,
+            Summation::Neumaier => self.sum + self.comp,
+        }
+    }
+}
+
+// Wektor stanu akumulowany wybraną metodą sumowania
+struct CompensatedState {
+    parts: Vec<Accumulator>,
+    mode: Summation,
+}
+
+impl CompensatedState {
+    fn new(y0: &[f64], mode: Summation) -> Self {
+        CompensatedState {
+            parts: y0.iter().map(|&v| Accumulator::new(v)).collect(),
+            mode,
+        }
+    }
+
+    fn add(&mut self, increments: &[f64]) {
+        for (part, &inc) in self.parts.iter_mut().zip(increments) {
+            part.add(inc, self.mode);
+        }
+    }
+
+    fn values(&self) -> Vec<f64> {
+        self.parts.iter().map(|p| p.value(self.mode)).collect()
+    }
+}
+
+// Przyrost stanu w jednym kroku metody Eulera
+fn euler_increments<F>(f: &F, y: &[f64], t: f64, dt: f64) -> Vec<f64>
 where
-    F: Fn(&Vec<f64>, f64) -> Vec<f64>,
+    F: Fn(&[f64], f64) -> Vec<f64>,
 {
-    let mut y = y0.clone();
-    let mut result = vec![y.clone()];
-    let mut t = t0;
+    f(y, t).iter().map(|&d| dt * d).collect()
+}
+
+// Przyrost stanu w jednym kroku metody Rungego-Kutty 4. rzędu
+fn rk4_increments<F>(f: &F, y: &[f64], t: f64, dt: f64) -> Vec<f64>
+where
+    F: Fn(&[f64], f64) -> Vec<f64>,
+{
+    let k1 = f(y, t);
+    
+    let mut y_temp = y.to_vec();
+    for i in 0..y.len() {
+        y_temp[i] += dt * k1[i] / 2.0;
+    }
+    let k2 = f(&y_temp, t + dt / 2.0);
+    
+    let mut y_temp = y.to_vec();
+    for i in 0..y.len() {
+        y_temp[i] += dt * k2[i] / 2.0;
+    }
+    let k3 = f(&y_temp, t + dt / 2.0);
+    
+    let mut y_temp = y.to_vec();
+    for i in 0..y.len() {
+        y_temp[i] += dt * k3[i];
+    }
+    let k4 = f(&y_temp, t + dt);
+    
+    (0..y.len())
+        .map(|i| dt * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]) / 6.0)
+        .collect()
+}
+
+// Pętla symulacji wykonywana krok po kroku. Z niej korzystają zarówno
+// obliczenia całego przebiegu, jak i podgląd na żywo w terminalu.
+pub struct Integrator<F> {
+    f: F,
+    method: Method,
+    dt: f64,
+    summation: Summation,
+    state: CompensatedState,
+    time: Accumulator,
+    y: Vec<f64>,
+}
+
+impl<F> Integrator<F>
+where
+    F: Fn(&[f64], f64) -> Vec<f64>,
+{
+    pub fn new(method: Method, f: F, y0: Vec<f64>, t0: f64, dt: f64, summation: Summation) -> Self {
+        Integrator {
+            f,
+            method,
+            dt,
+            summation,
+            state: CompensatedState::new(&y0, summation),
+            time: Accumulator::new(t0),
+            y: y0,
+        }
+    }
+
+    // Wykonuje jeden krok i zwraca nowy stan
+    pub fn step(&mut self) -> &[f64] {
+        let t = self.time.value(self.summation);
+        let increments = match self.method {
+            Method::Euler => euler_increments(&self.f, &self.y, t, self.dt),
+            Method::Rk4 => rk4_increments(&self.f, &self.y, t, self.dt),
+        };
+        self.state.add(&increments);
+        self.y = self.state.values();
+        self.time.add(self.dt, self.summation);
+        &self.y
+    }
+
+    pub fn state(&self) -> &[f64] {
+        &self.y
+    }
 
-    for _ in 0..steps {
-        let dy = f(&y, t);
-        for i in 0..y.len() {
-            y[i] += dt * dy[i];
+    pub fn time(&self) -> f64 {
+        self.time.value(self.summation)
+    }
+
+    // Wykonuje `steps` kroków, zwracając wszystkie stany łącznie z początkowym
+    pub fn run(mut self, steps: usize) -> Vec<Vec<f64>> {
+        let mut result = Vec::with_capacity(steps + 1);
+        result.push(self.y.clone());
+        for _ in 0..steps {
+            result.push(self.step().to_vec());
         }
-        result.push(y.clone());
-        t += dt;
+        result
     }
+}
 
-    result
+// Metoda Eulera
+pub fn euler<F>(f: F, y0: Vec<f64>, t0: f64, dt: f64, steps: usize, summation: Summation) -> Vec<Vec<f64>>
+where
+    F: Fn(&[f64], f64) -> Vec<f64>,
+{
+    Integrator::new(Method::Euler, f, y0, t0, dt, summation).run(steps)
 }
 
 // Runge-Kutta 4th order method
-pub fn rk4<F>(f: F, y0: Vec<f64>, t0: f64, dt: f64, steps: usize) -> Vec<Vec<f64>>
+pub fn rk4<F>(f: F, y0: Vec<f64>, t0: f64, dt: f64, steps: usize, summation: Summation) -> Vec<Vec<f64>>
 where
-    F: Fn(&Vec<f64>, f64) -> Vec<f64>,
+    F: Fn(&[f64], f64) -> Vec<f64>,
 {
-    let mut y = y0.clone();
-    let mut result = vec![y.clone()];
-    let mut t = t0;
+    Integrator::new(Method::Rk4, f, y0, t0, dt, summation).run(steps)
+}
 
-    for _ in 0..steps {
-        let k1 = f(&y, t);
-        
-        let mut y_temp = y.clone();
-        for i in 0..y.len() {
-            y_temp[i] += dt * k1[i] / 2.0;
-        }
-        let k2 = f(&y_temp, t + dt / 2.0);
-        
-        let mut y_temp = y.clone();
-        for i in 0..y.len() {
-            y_temp[i] += dt * k2[i] / 2.0;
+// Dostępne metody całkowania
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    Euler,
+    Rk4,
+}
+
+impl Method {
+    pub const ALL: [Method; 2] = [Method::Euler, Method::Rk4];
+
+    pub fn parse(name: &str) -> Option<Method> {
+        match name.to_lowercase().as_str() {
+            "euler" => Some(Method::Euler),
+            "rk4" => Some(Method::Rk4),
+            _ => None,
         }
-        let k3 = f(&y_temp, t + dt / 2.0);
-        
-        let mut y_temp = y.clone();
-        for i in 0..y.len() {
-            y_temp[i] += dt * k3[i];
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::Euler => "Euler",
+            Method::Rk4 => "RK4",
         }
-        let k4 = f(&y_temp, t + dt);
-        
-        for i in 0..y.len() {
-            y[i] += dt * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]) / 6.0;
+    }
+
+    // Przedrostek nazw plików wynikowych
+    pub fn file_stem(&self) -> &'static str {
+        match self {
+            Method::Euler => "euler",
+            Method::Rk4 => "rk4",
         }
-        
-        result.push(y.clone());
-        t += dt;
     }
 
-    result
+    pub fn integrate<F>(&self, f: F, y0: Vec<f64>, t0: f64, dt: f64, steps: usize, summation: Summation) -> Vec<Vec<f64>>
+    where
+        F: Fn(&[f64], f64) -> Vec<f64>,
+    {
+        match self {
+            Method::Euler => euler(f, y0, t0, dt, steps, summation),
+            Method::Rk4 => rk4(f, y0, t0, dt, steps, summation),
+        }
+    }
 }
 
-// Add this function to calculate total energy
-pub fn calculate_energy(state: &Vec<f64>) -> f64 {
-    let m1 = 1.0;
-    let m2 = 1.0;
-    let m3 = 1.0;
+// Chwile odpowiadające kolejnym stanom przebiegu o stałym kroku
+pub fn time_points(t0: f64, dt: f64, len: usize) -> Vec<f64> {
+    (0..len).map(|i| t0 + i as f64 * dt).collect()
+}
 
-    // Positions
+// Odległości między parami ciał (r12, r13, r23)
+pub fn pair_distances(state: &[f64]) -> [f64; 3] {
     let (x1, y1, x2, y2, x3, y3) = (state[0], state[1], state[2], state[3], state[4], state[5]);
-    // Velocities
+    [
+        ((x2-x1).powi(2) + (y2-y1).powi(2)).sqrt(),
+        ((x3-x1).powi(2) + (y3-y1).powi(2)).sqrt(),
+        ((x3-x2).powi(2) + (y3-y2).powi(2)).sqrt(),
+    ]
+}
+
+// Energia kinetyczna układu
+pub fn kinetic_energy(state: &[f64]) -> f64 {
+    let [m1, m2, m3] = MASSES;
     let (vx1, vy1, vx2, vy2, vx3, vy3) = (state[6], state[7], state[8], state[9], state[10], state[11]);
 
-    // Kinetic energy
-    let kinetic = 0.5 * m1 * (vx1*vx1 + vy1*vy1) + 
-                  0.5 * m2 * (vx2*vx2 + vy2*vy2) + 
-                  0.5 * m3 * (vx3*vx3 + vy3*vy3);
+    0.5 * m1 * (vx1*vx1 + vy1*vy1) +
+    0.5 * m2 * (vx2*vx2 + vy2*vy2) +
+    0.5 * m3 * (vx3*vx3 + vy3*vy3)
+}
+
+// Energia potencjalna oddziaływania grawitacyjnego
+pub fn potential_energy(state: &[f64]) -> f64 {
+    let [m1, m2, m3] = MASSES;
+    let [r12, r13, r23] = pair_distances(state);
 
-    // Distances between bodies
-    let r12 = ((x2-x1).powi(2) + (y2-y1).powi(2)).sqrt();
-    let r13 = ((x3-x1).powi(2) + (y3-y1).powi(2)).sqrt();
-    let r23 = ((x3-x2).powi(2) + (y3-y2).powi(2)).sqrt();
+    -G * ((m1*m2/r12) + (m1*m3/r13) + (m2*m3/r23))
+}
 
-    // Potential energy
-    let potential = -G * ((m1*m2/r12) + (m1*m3/r13) + (m2*m3/r23));
+// Całkowita energia układu
+pub fn calculate_energy(state: &[f64]) -> f64 {
+    kinetic_energy(state) + potential_energy(state)
+}
 
-    kinetic + potential
-}
\ No newline at end of file
+// Model oddziaływania grawitacyjnego
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gravity {
+    Newton,
+    PostNewtonian(f64), // z poprawkami 1PN, parametrem jest prędkość światła
+}
+
+impl Gravity {
+    // Pochodna stanu układu 3 ciał
+    pub fn derivative(&self, y: &[f64], t: f64) -> Vec<f64> {
+        match self {
+            Gravity::Newton => three_body(y, t),
+            Gravity::PostNewtonian(c) => post_newtonian::derivative(y, &MASSES, *c),
+        }
+    }
+
+    // Energia zachowywana w danym modelu
+    pub fn energy(&self, state: &[f64]) -> f64 {
+        match self {
+            Gravity::Newton => calculate_energy(state),
+            Gravity::PostNewtonian(c) => post_newtonian::energy(state, &MASSES, *c),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Gravity::Newton => "Newton".to_string(),
+            Gravity::PostNewtonian(c) => format!("1PN (c = {})", c),
+        }
+    }
+}
+
+// Względny błąd energii |E(t) - E(0)| / |E(0)| dla każdego zapisanego stanu
+pub fn energy_errors(data: &[Vec<f64>], gravity: Gravity) -> Vec<f64> {
+    let initial_energy = gravity.energy(&data[0]);
+    data.iter()
+        .map(|state| (gravity.energy(state) - initial_energy).abs() / initial_energy.abs())
+        .collect()
+}
+
+// Położenie i prędkość środka masy (x, y, vx, vy)
+pub fn center_of_mass(state: &[f64]) -> (f64, f64, f64, f64) {
+    let total: f64 = MASSES.iter().sum();
+    let mut com = (0.0, 0.0, 0.0, 0.0);
+    for (body, m) in MASSES.iter().enumerate() {
+        com.0 += m * state[2 * body];
+        com.1 += m * state[2 * body + 1];
+        com.2 += m * state[6 + 2 * body];
+        com.3 += m * state[7 + 2 * body];
+    }
+    (com.0 / total, com.1 / total, com.2 / total, com.3 / total)
+}
+
+// Całkowity pęd układu (px, py)
+pub fn linear_momentum(state: &[f64]) -> (f64, f64) {
+    MASSES.iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(px, py), (body, m)| (px + m * state[6 + 2 * body], py + m * state[7 + 2 * body]))
+}
+
+// Całkowity moment pędu (składowa z) układu
+pub fn angular_momentum(state: &[f64]) -> f64 {
+    MASSES.iter()
+        .enumerate()
+        .map(|(body, m)| m * (state[2 * body] * state[7 + 2 * body] - state[2 * body + 1] * state[6 + 2 * body]))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum(values: impl IntoIterator<Item = f64>, mode: Summation) -> f64 {
+        let mut values = values.into_iter();
+        let mut acc = Accumulator::new(values.next().unwrap_or(0.0));
+        for x in values {
+            acc.add(x, mode);
+        }
+        acc.value(mode)
+    }
+
+    #[test]
+    fn compensated_sums_keep_small_increments() {
+        // Każdy przyrost 1e-16 jest mniejszy niż połowa ulp(1.0), więc zwykłe
+        // dodawanie gubi je wszystkie
+        let values = || std::iter::once(1.0).chain(std::iter::repeat_n(1e-16, 1_000_000));
+        let exact = 1.0 + 1e-10;
+        assert_eq!(sum(values(), Summation::Naive), 1.0);
+        for mode in [Summation::Kahan, Summation::Neumaier] {
+            let result = sum(values(), mode);
+            assert!((result - exact).abs() < 1e-15, "{}: {}", mode.name(), result);
+        }
+    }
+
+    #[test]
+    fn neumaier_handles_increment_larger_than_sum() {
+        // Kahan traci 1.0 przy dodawaniu 1e100; Neumaier przechowuje je w poprawce
+        let values = [1e100, 1.0, -1e100];
+        assert_eq!(sum(values, Summation::Naive), 0.0);
+        assert_eq!(sum(values, Summation::Kahan), 0.0);
+        assert_eq!(sum(values, Summation::Neumaier), 1.0);
+    }
+}
@@ -0,0 +1,285 @@
+use crate::manifest::PrecessionRecord;
+use crate::physics::{Integrator, Method, Summation, G};
+use std::f64::consts::PI;
+use std::time::Instant;
+
+// Poprawki post-newtonowskie pierwszego rzędu (1PN) - równania
+// Einsteina-Infelda-Hoffmanna w cechowaniu harmonicznym, dla N ciał w 2D.
+// Układ stanu jak w `three_body`: najpierw położenia (x1, y1, ..., xN, yN),
+// potem prędkości.
+
+type Vec2 = (f64, f64);
+
+fn dot(a: Vec2, b: Vec2) -> f64 {
+    a.0 * b.0 + a.1 * b.1
+}
+
+fn position(y: &[f64], body: usize) -> Vec2 {
+    (y[2 * body], y[2 * body + 1])
+}
+
+fn velocity(y: &[f64], n: usize, body: usize) -> Vec2 {
+    (y[2 * n + 2 * body], y[2 * n + 2 * body + 1])
+}
+
+// Przyspieszenia newtonowskie oraz potencjały Σ_{b≠a} G m_b / r_ab w miejscu każdego ciała
+fn newtonian_terms(y: &[f64], masses: &[f64]) -> (Vec<Vec2>, Vec<f64>) {
+    let n = masses.len();
+    let mut accelerations = vec![(0.0, 0.0); n];
+    let mut potentials = vec![0.0; n];
+    for a in 0..n {
+        for b in (0..n).filter(|&b| b != a) {
+            let (xa, xb) = (position(y, a), position(y, b));
+            let d = (xb.0 - xa.0, xb.1 - xa.1);
+            let r = dot(d, d).sqrt();
+            accelerations[a].0 += G * masses[b] * d.0 / r.powi(3);
+            accelerations[a].1 += G * masses[b] * d.1 / r.powi(3);
+            potentials[a] += G * masses[b] / r;
+        }
+    }
+    (accelerations, potentials)
+}
+
+// Pochodna stanu z siłą newtonowską i poprawką 1PN; `c` to prędkość światła
+pub fn derivative(y: &[f64], masses: &[f64], c: f64) -> Vec<f64> {
+    let n = masses.len();
+    let c2 = c * c;
+    let (newton, potentials) = newtonian_terms(y, masses);
+
+    let mut result = vec![0.0; 4 * n];
+    for a in 0..n {
+        let (xa, va) = (position(y, a), velocity(y, n, a));
+        let mut acc = newton[a];
+        for b in (0..n).filter(|&b| b != a) {
+            let (xb, vb) = (position(y, b), velocity(y, n, b));
+            let r = ((xa.0 - xb.0).powi(2) + (xa.1 - xb.1).powi(2)).sqrt();
+            let n_ab = ((xa.0 - xb.0) / r, (xa.1 - xb.1) / r);
+            let gm = G * masses[b];
+
+            // Poprawka wzdłuż kierunku do ciała b
+            let bracket = dot(va, va) + 2.0 * dot(vb, vb) - 4.0 * dot(va, vb)
+                - 1.5 * dot(n_ab, vb).powi(2)
+                - 4.0 * potentials[a] - potentials[b]
+                + 0.5 * dot((xb.0 - xa.0, xb.1 - xa.1), newton[b]);
+            acc.0 -= gm * n_ab.0 / (r * r) * bracket / c2;
+            acc.1 -= gm * n_ab.1 / (r * r) * bracket / c2;
+
+            // Poprawka wzdłuż prędkości względnej
+            let along = gm / (r * r) * dot(n_ab, (4.0 * va.0 - 3.0 * vb.0, 4.0 * va.1 - 3.0 * vb.1)) / c2;
+            acc.0 += along * (va.0 - vb.0);
+            acc.1 += along * (va.1 - vb.1);
+
+            // Przyspieszenie ciała b przenoszone przez pole grawitacyjne
+            acc.0 += 3.5 * gm * newton[b].0 / r / c2;
+            acc.1 += 3.5 * gm * newton[b].1 / r / c2;
+        }
+
+        result[2 * a] = va.0;
+        result[2 * a + 1] = va.1;
+        result[2 * n + 2 * a] = acc.0;
+        result[2 * n + 2 * a + 1] = acc.1;
+    }
+    result
+}
+
+// Energia zachowywana przez ruch 1PN (bez energii spoczynkowej)
+pub fn energy(state: &[f64], masses: &[f64], c: f64) -> f64 {
+    let n = masses.len();
+    let (_, potentials) = newtonian_terms(state, masses);
+
+    let mut newtonian = 0.0;
+    let mut correction = 0.0;
+    for a in 0..n {
+        let (xa, va) = (position(state, a), velocity(state, n, a));
+        let v2 = dot(va, va);
+        newtonian += 0.5 * masses[a] * v2 - 0.5 * masses[a] * potentials[a];
+        correction += 0.375 * masses[a] * v2 * v2
+            + 0.5 * masses[a] * potentials[a].powi(2); // Σ_a Σ_b Σ_c G² m_a m_b m_c / (2 r_ab r_ac)
+        for b in (0..n).filter(|&b| b != a) {
+            let (xb, vb) = (position(state, b), velocity(state, n, b));
+            let r = ((xa.0 - xb.0).powi(2) + (xa.1 - xb.1).powi(2)).sqrt();
+            let n_ab = ((xa.0 - xb.0) / r, (xa.1 - xb.1) / r);
+            correction += G * masses[a] * masses[b] / r
+                * (1.5 * v2 - 1.75 * dot(va, vb) - 0.25 * dot(n_ab, va) * dot(n_ab, vb));
+        }
+    }
+    newtonian + correction / (c * c)
+}
+
+pub struct PrecessionCheck {
+    pub masses: [f64; 2],
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub c: f64,
+    pub orbits: usize,
+    pub steps_per_orbit: usize,
+    pub tolerance: f64, // dopuszczalny błąd względny tempa precesji
+}
+
+impl Default for PrecessionCheck {
+    fn default() -> Self {
+        PrecessionCheck {
+            masses: [0.5, 0.5],
+            semi_major_axis: 1.0,
+            eccentricity: 0.5,
+            c: 100.0,
+            orbits: 20,
+            steps_per_orbit: 5000,
+            tolerance: 0.01,
+        }
+    }
+}
+
+// Kąt peryhelium (kierunek wektora względnego położenia w chwili,
+// gdy r·v zmienia znak z ujemnego na dodatni)
+fn periastron_angles(data: &[Vec<f64>]) -> Vec<f64> {
+    let relative = |s: &[f64]| ((s[2] - s[0], s[3] - s[1]), (s[6] - s[4], s[7] - s[5]));
+    let mut angles = Vec::new();
+    for pair in data.windows(2) {
+        let (r0, v0) = relative(&pair[0]);
+        let (r1, v1) = relative(&pair[1]);
+        let (s0, s1) = (dot(r0, v0), dot(r1, v1));
+        if s0 < 0.0 && s1 >= 0.0 {
+            // Liniowa interpolacja chwili, w której r·v = 0
+            let f = s0 / (s0 - s1);
+            let x = r0.0 + f * (r1.0 - r0.0);
+            let y = r0.1 + f * (r1.1 - r0.1);
+            angles.push(y.atan2(x));
+        }
+    }
+    // Usunięcie skoków o 2π
+    for i in 1..angles.len() {
+        while angles[i] - angles[i - 1] > PI {
+            angles[i] -= 2.0 * PI;
+        }
+        while angles[i] - angles[i - 1] < -PI {
+            angles[i] += 2.0 * PI;
+        }
+    }
+    angles
+}
+
+// Stan początkowy testu precesji: start w peryhelium, w układzie środka
+// masy, z newtonowską prędkością orbitalną
+pub fn precession_initial_state(check: &PrecessionCheck) -> Vec<f64> {
+    let [m1, m2] = check.masses;
+    let total = m1 + m2;
+    let (a, e) = (check.semi_major_axis, check.eccentricity);
+    let r_p = a * (1.0 - e);
+    let v_p = (G * total * (1.0 + e) / (a * (1.0 - e))).sqrt();
+    vec![
+        -m2 / total * r_p, 0.0,
+        m1 / total * r_p, 0.0,
+        0.0, -m2 / total * v_p,
+        0.0, m1 / total * v_p,
+    ]
+}
+
+// Mierzy przesunięcie peryhelium orbity dwóch ciał i porównuje je z wartością
+// analityczną Δφ = 6πGM / (c² a (1 - e²)) na jeden obieg. Wynik trafia do
+// manifestu także wtedy, gdy przekracza tolerancję - to sprawdza wywołujący
+// (`PrecessionRecord::passed`).
+pub fn precession_check(check: &PrecessionCheck) -> Result<PrecessionRecord, Box<dyn std::error::Error>> {
+    let [m1, m2] = check.masses;
+    let total = m1 + m2;
+    let (a, e) = (check.semi_major_axis, check.eccentricity);
+    let y0 = precession_initial_state(check);
+
+    let period = 2.0 * PI * (a.powi(3) / (G * total)).sqrt();
+    let dt = period / check.steps_per_orbit as f64;
+    // Pół obiegu zapasu, żeby ostatnie peryhelium na pewno wypadło w przebiegu
+    let steps = (check.orbits * check.steps_per_orbit) + check.steps_per_orbit / 2;
+
+    let masses = check.masses;
+    let c = check.c;
+    let start = Instant::now();
+    let data = Integrator::new(Method::Rk4, |y: &[f64], _t| derivative(y, &masses, c), y0, 0.0, dt, Summation::Naive)
+        .run(steps);
+    let wall_time = start.elapsed();
+
+    let angles = periastron_angles(&data);
+    if angles.len() < 3 {
+        return Err("Za mało przejść przez peryhelium do pomiaru precesji".into());
+    }
+
+    // Tempo precesji: nachylenie prostej dopasowanej do kątów kolejnych peryheliów
+    let count = angles.len() as f64;
+    let mean_k = (count - 1.0) / 2.0;
+    let mean_angle = angles.iter().sum::<f64>() / count;
+    let (num, den) = angles.iter().enumerate().fold((0.0, 0.0), |(num, den), (k, &phi)| {
+        let dk = k as f64 - mean_k;
+        (num + dk * (phi - mean_angle), den + dk * dk)
+    });
+    let measured = num / den;
+    let analytic = 6.0 * PI * G * total / (c * c * a * (1.0 - e * e));
+    let relative_error = (measured - analytic).abs() / analytic;
+
+    let e0 = energy(&data[0], &masses, c);
+    let energy_drift = data.iter()
+        .map(|s| (energy(s, &masses, c) - e0).abs() / e0.abs())
+        .fold(0.0, f64::max);
+
+    println!("Masy: {} i {}, a = {}, e = {}, c = {}", m1, m2, a, e, c);
+    println!("Liczba peryheliów: {}", angles.len());
+    println!("Precesja na obieg (pomiar):    {:.6e} rad", measured);
+    println!("Precesja na obieg (analitycz.): {:.6e} rad", analytic);
+    println!("Błąd względny: {:.3e} (tolerancja {:.1e})", relative_error, check.tolerance);
+    println!("Maksymalny względny błąd energii 1PN: {:.3e}", energy_drift);
+
+    Ok(PrecessionRecord {
+        masses: check.masses,
+        semi_major_axis: a,
+        eccentricity: e,
+        c,
+        method: Method::Rk4.file_stem().to_string(),
+        dt,
+        steps,
+        wall_time_s: wall_time.as_secs_f64(),
+        periastra: angles.len(),
+        measured_rate: measured,
+        analytic_rate: analytic,
+        relative_error,
+        tolerance: check.tolerance,
+        energy_error: energy_drift,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::{calculate_energy, three_body, MASSES};
+
+    #[test]
+    fn short_precession_run_matches_analytic_rate() {
+        let check = PrecessionCheck {
+            orbits: 4,
+            steps_per_orbit: 2000,
+            tolerance: 0.05,
+            ..PrecessionCheck::default()
+        };
+        let record = precession_check(&check).unwrap();
+        let ratio = record.measured_rate / record.analytic_rate;
+        assert!((ratio - 1.0).abs() < check.tolerance, "pomiar / teoria = {}", ratio);
+        assert!(record.passed());
+    }
+
+    #[test]
+    fn large_c_reduces_to_newton() {
+        let y = [
+            -1.0, 0.2, 1.0, -0.1, 0.1, 0.8,
+            0.3, 0.4, -0.2, 0.5, -0.1, -0.9,
+        ];
+        let newton = three_body(&y, 0.0);
+        let pn = derivative(&y, &MASSES, 1e8);
+        for (i, (a, b)) in newton.iter().zip(&pn).enumerate() {
+            assert!((a - b).abs() < 1e-12 * a.abs().max(1.0), "składowa {}: {} vs {}", i, a, b);
+        }
+
+        let e_newton = calculate_energy(&y);
+        let e_pn = energy(&y, &MASSES, 1e8);
+        assert!((e_newton - e_pn).abs() < 1e-12 * e_newton.abs(), "{} vs {}", e_newton, e_pn);
+
+        // Przy skończonym c poprawka jest niezerowa
+        assert!(derivative(&y, &MASSES, 10.0) != newton);
+    }
+}
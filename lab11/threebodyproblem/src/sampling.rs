@@ -0,0 +1,125 @@
+// Próbkowanie przebiegu w równych odstępach czasu fizycznego.
+// Stan w chwilach pomiędzy krokami integratora wyznaczamy interpolacją Hermite'a
+// (wielomian 3. stopnia zgodny z położeniem i prędkością na obu końcach kroku).
+
+// Stan przebiegu w wybranej chwili
+pub struct Sample {
+    pub time: f64,
+    pub step: usize, // ostatni krok integratora nie późniejszy niż `time`
+    pub state: Vec<f64>,
+}
+
+// Interpolacja Hermite'a stanu (x1, y1, ..., vx1, vy1, ...) pomiędzy dwoma krokami
+pub fn hermite_interpolate(a: &[f64], b: &[f64], t0: f64, t1: f64, t: f64) -> Vec<f64> {
+    let h = t1 - t0;
+    let s = if h > 0.0 { (t - t0) / h } else { 0.0 };
+    let s2 = s * s;
+    let s3 = s2 * s;
+
+    // Bazowe wielomiany Hermite'a i ich pochodne po s
+    let h00 = 2.0 * s3 - 3.0 * s2 + 1.0;
+    let h10 = s3 - 2.0 * s2 + s;
+    let h01 = -2.0 * s3 + 3.0 * s2;
+    let h11 = s3 - s2;
+    let d00 = 6.0 * s2 - 6.0 * s;
+    let d10 = 3.0 * s2 - 4.0 * s + 1.0;
+    let d01 = -6.0 * s2 + 6.0 * s;
+    let d11 = 3.0 * s2 - 2.0 * s;
+
+    let n = a.len() / 2; // liczba współrzędnych położenia
+    let mut state = vec![0.0; a.len()];
+    for i in 0..n {
+        let (p0, p1) = (a[i], b[i]);
+        let (v0, v1) = (a[n + i], b[n + i]);
+        state[i] = h00 * p0 + h10 * h * v0 + h01 * p1 + h11 * h * v1;
+        // Prędkość jako pochodna tego samego wielomianu
+        state[n + i] = if h > 0.0 {
+            (d00 * p0 + d01 * p1) / h + d10 * v0 + d11 * v1
+        } else {
+            v0
+        };
+    }
+    state
+}
+
+// Próbki co `interval` jednostek czasu, od pierwszej do ostatniej chwili przebiegu
+pub fn sample_uniform(data: &[Vec<f64>], times: &[f64], interval: f64) -> Vec<Sample> {
+    let (start, end) = (times[0], times[times.len() - 1]);
+    let count = ((end - start) / interval).floor() as usize + 1;
+
+    let mut samples = Vec::with_capacity(count);
+    let mut step = 0;
+    for k in 0..count {
+        let time = start + k as f64 * interval;
+        while step + 1 < times.len() && times[step + 1] <= time {
+            step += 1;
+        }
+        let state = if step + 1 < times.len() {
+            hermite_interpolate(&data[step], &data[step + 1], times[step], times[step + 1], time)
+        } else {
+            data[step].clone()
+        };
+        samples.push(Sample { time, step, state });
+    }
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trajektoria sześcienna (x, y, vx, vy) - interpolacja Hermite'a ma ją odtwarzać dokładnie
+    fn cubic(t: f64) -> Vec<f64> {
+        vec![
+            t.powi(3) - 2.0 * t * t + 0.5 * t + 1.0,
+            -0.5 * t.powi(3) + t,
+            3.0 * t * t - 4.0 * t + 0.5,
+            -1.5 * t * t + 1.0,
+        ]
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64], context: f64) {
+        for (i, (a, e)) in actual.iter().zip(expected).enumerate() {
+            assert!((a - e).abs() < 1e-12, "t = {}, składowa {}: {} vs {}", context, i, a, e);
+        }
+    }
+
+    #[test]
+    fn hermite_reproduces_endpoints_exactly() {
+        let (t0, t1) = (0.3, 0.8);
+        let (a, b) = (cubic(t0), cubic(t1));
+        assert_eq!(hermite_interpolate(&a, &b, t0, t1, t0), a);
+        assert_eq!(hermite_interpolate(&a, &b, t0, t1, t1), b);
+    }
+
+    #[test]
+    fn hermite_is_exact_for_cubic_trajectory() {
+        let (t0, t1) = (0.3, 0.8);
+        let (a, b) = (cubic(t0), cubic(t1));
+        for k in 0..=10 {
+            let t = t0 + (t1 - t0) * k as f64 / 10.0;
+            assert_close(&hermite_interpolate(&a, &b, t0, t1, t), &cubic(t), t);
+        }
+    }
+
+    #[test]
+    fn frames_are_evenly_spaced_when_dt_does_not_divide_interval() {
+        // Krok 0.01, klatki co time_per_second / fps = 0.5 / 24 - nie jest wielokrotnością kroku
+        let dt = 0.01;
+        let times: Vec<f64> = (0..=100).map(|k| k as f64 * dt).collect();
+        let data: Vec<Vec<f64>> = times.iter().map(|&t| cubic(t)).collect();
+        let interval = 0.5 / 24.0;
+
+        let samples = sample_uniform(&data, &times, interval);
+        assert_eq!(samples.len(), 49);
+        for (k, sample) in samples.iter().enumerate() {
+            assert_eq!(sample.time, k as f64 * interval, "klatka {}", k);
+            assert!(times[sample.step] <= sample.time, "klatka {}", k);
+            assert!(sample.step + 1 == times.len() || sample.time < times[sample.step + 1], "klatka {}", k);
+            assert_close(&sample.state, &cubic(sample.time), sample.time);
+        }
+        for pair in samples.windows(2) {
+            assert!((pair[1].time - pair[0].time - interval).abs() < 1e-15);
+        }
+    }
+}
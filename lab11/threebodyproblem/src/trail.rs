@@ -0,0 +1,242 @@
+use plotters::coord::types::RangedCoordf64;
+use plotters::prelude::*;
+use crate::physics::{energy_errors, Gravity};
+use crate::sampling::Sample;
+
+// Jak długo ślad ciała pozostaje widoczny na animacji
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrailMode {
+    Full,         // cały dotychczasowy tor
+    Fixed(usize), // tylko ostatnie N klatek
+    Fade(usize),  // ostatnie N klatek, starsze fragmenty stopniowo znikają
+}
+
+impl TrailMode {
+    // Format: "full", "fixed:N" albo "fade:N"
+    pub fn parse(spec: &str) -> Option<TrailMode> {
+        let (name, length) = match spec.split_once(':') {
+            Some((name, length)) => (name, Some(length.parse::<usize>().ok()?)),
+            None => (spec, None),
+        };
+        match (name, length) {
+            ("full", None) => Some(TrailMode::Full),
+            ("fixed", Some(n)) if n > 0 => Some(TrailMode::Fixed(n)),
+            ("fade", Some(n)) if n > 0 => Some(TrailMode::Fade(n)),
+            _ => None,
+        }
+    }
+}
+
+// Kolorowanie śladu
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrailColor {
+    Body,        // kolor ciała
+    Speed,       // prędkość ciała (mapa viridis)
+    EnergyError, // względny błąd energii układu, w skali logarytmicznej
+}
+
+impl TrailColor {
+    pub fn parse(name: &str) -> Option<TrailColor> {
+        match name {
+            "body" => Some(TrailColor::Body),
+            "speed" => Some(TrailColor::Speed),
+            "energy" => Some(TrailColor::EnergyError),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct TrailPoint {
+    pub x: f64,
+    pub y: f64,
+    pub frame: usize, // klatka, w której punkt został dopisany
+    pub value: f64,   // wielkość używana do kolorowania
+}
+
+// Najwięcej punktów śladu jednego ciała rysowanych w jednej klatce
+const MAX_DRAWN_POINTS: usize = 2000;
+
+// Ślady wszystkich ciał budowane przyrostowo. Punkty są tylko dopisywane
+// (z pominięciem tych bliższych niż `min_spacing` od poprzedniego), więc
+// budowa śladów kosztuje liniowo względem liczby kroków. Każda klatka
+// rysuje wycinek tej samej tablicy, przerzedzony do najwyżej
+// MAX_DRAWN_POINTS punktów na ciało - koszt klatki nie rośnie z długością
+// śladu, a całej animacji jest liniowy względem liczby klatek.
+pub struct Trails {
+    points: [Vec<TrailPoint>; 3],
+    frame_ends: Vec<[usize; 3]>,
+    next_step: usize, // pierwszy krok integratora jeszcze nie dopisany do śladu
+    values: Vec<f64>,
+    value_range: (f64, f64),
+    min_spacing: f64,
+    mode: TrailMode,
+    color: TrailColor,
+}
+
+impl Trails {
+    pub fn new(data: &[Vec<f64>], min_spacing: f64, mode: TrailMode, color: TrailColor, gravity: Gravity) -> Self {
+        // Wartości do kolorowania liczymy raz dla całego przebiegu,
+        // żeby skala kolorów była ta sama we wszystkich klatkach
+        let values = match color {
+            TrailColor::Body | TrailColor::Speed => Vec::new(),
+            TrailColor::EnergyError => energy_errors(data, gravity)
+                .iter()
+                .map(|e| e.max(1e-16).log10())
+                .collect(),
+        };
+
+        Trails {
+            points: [Vec::new(), Vec::new(), Vec::new()],
+            frame_ends: Vec::new(),
+            next_step: 0,
+            values,
+            value_range: (f64::INFINITY, f64::NEG_INFINITY),
+            min_spacing,
+            mode,
+            color,
+        }
+    }
+
+    fn value_at(&self, state: &[f64], step: usize, body: usize) -> f64 {
+        match self.color {
+            TrailColor::Body => 0.0,
+            TrailColor::Speed => state[6 + 2 * body].hypot(state[7 + 2 * body]),
+            TrailColor::EnergyError => self.values[step],
+        }
+    }
+
+    // Dopisuje kroki integratora wykonane do chwili próbki oraz samą próbkę
+    // jako kolejną klatkę
+    pub fn push_frame(&mut self, data: &[Vec<f64>], sample: &Sample) {
+        let frame = self.frame_ends.len();
+        for (step, state) in data.iter().enumerate().take(sample.step + 1).skip(self.next_step) {
+            self.push_point(state, step, frame, false);
+        }
+        self.next_step = self.next_step.max(sample.step + 1);
+        // Próbkę zawsze dopisujemy, żeby ślad dochodził do ciała
+        self.push_point(&sample.state, sample.step, frame, true);
+        self.frame_ends.push([self.points[0].len(), self.points[1].len(), self.points[2].len()]);
+    }
+
+    fn push_point(&mut self, state: &[f64], step: usize, frame: usize, always: bool) {
+        for body in 0..3 {
+            let (x, y) = (state[2 * body], state[2 * body + 1]);
+            let far_enough = match self.points[body].last() {
+                Some(p) => (x - p.x).hypot(y - p.y) >= self.min_spacing,
+                None => true,
+            };
+            if far_enough || always {
+                let value = self.value_at(state, step, body);
+                self.value_range = (self.value_range.0.min(value), self.value_range.1.max(value));
+                self.points[body].push(TrailPoint { x, y, frame, value });
+            }
+        }
+    }
+
+    // Punkty śladu ciała widoczne w danej klatce (wszystkie, bez przerzedzenia)
+    pub fn visible(&self, body: usize, frame: usize) -> &[TrailPoint] {
+        let points = &self.points[body][..self.frame_ends[frame][body]];
+        let start = match self.mode {
+            TrailMode::Full => 0,
+            TrailMode::Fixed(n) | TrailMode::Fade(n) => {
+                points.partition_point(|p| p.frame + n <= frame)
+            }
+        };
+        &points[start..]
+    }
+
+    fn segment_color(&self, point: &TrailPoint, body_color: RGBColor, frame: usize) -> RGBAColor {
+        let base = match self.color {
+            TrailColor::Body => body_color,
+            TrailColor::Speed | TrailColor::EnergyError => {
+                let (min, max) = self.value_range;
+                ViridisRGB::get_color_normalized(point.value, min, max.max(min + 1e-12))
+            }
+        };
+        // Ślady w kolorze ciała są półprzezroczyste, jak wcześniej
+        let alpha = if self.color == TrailColor::Body { 0.3 } else { 0.8 };
+        let alpha = match self.mode {
+            TrailMode::Fade(n) => alpha * (1.0 - (frame - point.frame) as f64 / n as f64),
+            _ => alpha,
+        };
+        base.mix(alpha)
+    }
+
+    // Rysuje ślad jednego ciała na wykresie klatki
+    pub fn draw<DB: DrawingBackend>(
+        &self,
+        chart: &mut ChartContext<DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+        body: usize,
+        body_color: RGBColor,
+        frame: usize,
+    ) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+        let points = drawn_points(self.visible(body, frame));
+
+        // Jednolity ślad da się narysować jedną linią
+        if self.color == TrailColor::Body && !matches!(self.mode, TrailMode::Fade(_)) {
+            chart.draw_series(LineSeries::new(
+                points.iter().map(|p| (p.x, p.y)),
+                body_color.mix(0.3),
+            ))?;
+            return Ok(());
+        }
+
+        chart.draw_series(points.windows(2).map(|pair| {
+            PathElement::new(
+                vec![(pair[0].x, pair[0].y), (pair[1].x, pair[1].y)],
+                self.segment_color(&pair[1], body_color, frame),
+            )
+        }))?;
+        Ok(())
+    }
+}
+
+// Co który punkt śladu rysować, żeby było ich najwyżej MAX_DRAWN_POINTS;
+// ostatni (przy ciele) zostaje zawsze
+fn drawn_points(points: &[TrailPoint]) -> Vec<TrailPoint> {
+    // Jedno miejsce zostaje zarezerwowane na ostatni punkt
+    let stride = points.len().div_ceil(MAX_DRAWN_POINTS - 1).max(1);
+    let mut drawn: Vec<TrailPoint> = points.iter().step_by(stride).copied().collect();
+    if let Some(&last) = points.last()
+        && !(points.len() - 1).is_multiple_of(stride)
+    {
+        drawn.push(last);
+    }
+    drawn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(n: usize) -> Vec<TrailPoint> {
+        (0..n)
+            .map(|i| TrailPoint {
+                x: i as f64,
+                y: 0.0,
+                frame: i,
+                value: 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn short_trail_is_drawn_whole() {
+        let points = line(10);
+        let drawn = drawn_points(&points);
+        assert_eq!(drawn.len(), 10);
+        assert!(drawn_points(&[]).is_empty());
+    }
+
+    #[test]
+    fn long_trail_is_capped_and_reaches_the_body() {
+        for n in [MAX_DRAWN_POINTS, MAX_DRAWN_POINTS + 1, 12_345, 1_000_000] {
+            let points = line(n);
+            let drawn = drawn_points(&points);
+            assert!(drawn.len() <= MAX_DRAWN_POINTS, "{n}: {}", drawn.len());
+            assert_eq!(drawn.first().unwrap().frame, 0, "{n}");
+            assert_eq!(drawn.last().unwrap().frame, n - 1, "{n}");
+        }
+    }
+}
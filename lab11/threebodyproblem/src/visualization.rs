@@ -1,9 +1,20 @@
+use plotters::coord::Shift;
 use plotters::prelude::*;
-use crate::physics::calculate_energy;
+use crate::output::save_plot;
+use crate::physics::{energy_errors, kinetic_energy, pair_distances, potential_energy, Gravity, Method, Summation};
+
+type PlotResult = Result<(), Box<dyn std::error::Error>>;
 
 // Wykres torów
-pub fn draw_trajectories(data: &Vec<Vec<f64>>, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let root = BitMapBackend::new(filename, (800, 600)).into_drawing_area();
+#[allow(dead_code)]
+pub fn draw_trajectories(data: &[Vec<f64>], filename: &str) -> PlotResult {
+    save_plot!(filename, (800, 600), |root| draw_trajectories_on(root, data))
+}
+
+fn draw_trajectories_on<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, data: &[Vec<f64>]) -> PlotResult
+where
+    DB::ErrorType: 'static,
+{
     root.fill(&WHITE)?;
 
     let (x_vals, y_vals): (Vec<_>, Vec<_>) = data
@@ -17,7 +28,7 @@ pub fn draw_trajectories(data: &Vec<Vec<f64>>, filename: &str) -> Result<(), Box
     let y_range = y_vals.iter().cloned().fold(f64::INFINITY, f64::min)
         ..y_vals.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
 
-    let mut chart = ChartBuilder::on(&root)
+    let mut chart = ChartBuilder::on(root)
         .caption("Trajektorie 3 ciał (Euler)", ("sans-serif", 30))
         .margin(20)
         .x_label_area_size(40)
@@ -49,30 +60,100 @@ pub fn draw_trajectories(data: &Vec<Vec<f64>>, filename: &str) -> Result<(), Box
     Ok(())
 }
 
-pub fn draw_method_comparison_grid(method_name: &str, results: &[Vec<Vec<f64>>], dt_values: &[f64], filename: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let root = BitMapBackend::new(filename, (1000, 1000)).into_drawing_area();
+// Jeden przebieg symulacji wraz z opisem potrzebnym do wykresów
+pub struct Run {
+    pub method: Method,
+    pub dt: f64,
+    pub summation: Summation,
+    pub gravity: Gravity,
+    pub times: Vec<f64>, // chwile odpowiadające kolejnym stanom w `data`
+    pub data: Vec<Vec<f64>>,
+}
+
+impl Run {
+    pub fn label(&self) -> String {
+        match self.summation {
+            Summation::Naive => self.method.name().to_string(),
+            summation => format!("{} ({})", self.method.name(), summation.label()),
+        }
+    }
+
+    // Kolor serii: przebiegi z kompensacją dostają osobny kolor i linię przerywaną
+    fn color(&self) -> RGBColor {
+        match (self.method, self.summation) {
+            (Method::Euler, Summation::Naive) => RED,
+            (Method::Rk4, Summation::Naive) => BLUE,
+            (Method::Euler, _) => MAGENTA,
+            (Method::Rk4, _) => CYAN,
+        }
+    }
+}
+
+const PANEL_SIZE: u32 = 500; // rozmiar jednego panelu siatki w pikselach
+
+// Kształt siatki (wiersze, kolumny) możliwie bliski kwadratu
+pub fn grid_shape(panels: usize) -> (usize, usize) {
+    let cols = (panels as f64).sqrt().ceil().max(1.0) as usize;
+    let rows = panels.div_ceil(cols).max(1);
+    (rows, cols)
+}
+
+type Range2D = (std::ops::Range<f64>, std::ops::Range<f64>);
+
+// Zakres położeń wszystkich ciał w przebiegu
+fn position_range(data: &[Vec<f64>]) -> Range2D {
+    let (x_vals, y_vals): (Vec<_>, Vec<_>) = data
+        .iter()
+        .flat_map(|s| vec![s[0], s[2], s[4]])
+        .zip(data.iter().flat_map(|s| vec![s[1], s[3], s[5]]))
+        .unzip();
+
+    let x_range = x_vals.iter().cloned().fold(f64::INFINITY, f64::min)
+        ..x_vals.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let y_range = y_vals.iter().cloned().fold(f64::INFINITY, f64::min)
+        ..y_vals.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    (x_range, y_range)
+}
+
+fn union_range(a: &std::ops::Range<f64>, b: &std::ops::Range<f64>) -> std::ops::Range<f64> {
+    a.start.min(b.start)..a.end.max(b.end)
+}
+
+const MAX_SERIES_POINTS: usize = 50_000; // maksymalna liczba punktów jednej serii na wykresie
+
+// Co który stan rysować, żeby seria miała najwyżej MAX_SERIES_POINTS punktów
+// (przy dt = 1e-6 przebieg ma miliony stanów, a plik SVG/PDF rósłby z każdym)
+fn series_stride(len: usize) -> usize {
+    len.div_ceil(MAX_SERIES_POINTS).max(1)
+}
+
+// Siatka torów ciał, po jednym panelu na przebieg. Bez podanego układu
+// (wiersze, kolumny) kształt siatki wynika z liczby przebiegów.
+pub fn draw_trajectory_grid(runs: &[&Run], layout: Option<(usize, usize)>, shared_axes: bool, filename: &str) -> PlotResult {
+    let (rows, cols) = layout.unwrap_or_else(|| grid_shape(runs.len()));
+    let size = (cols as u32 * PANEL_SIZE, rows as u32 * PANEL_SIZE);
+    save_plot!(filename, size, |root| draw_trajectory_grid_on(root, runs, (rows, cols), shared_axes))
+}
+
+fn draw_trajectory_grid_on<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, runs: &[&Run], (rows, cols): (usize, usize), shared_axes: bool) -> PlotResult
+where
+    DB::ErrorType: 'static,
+{
     root.fill(&WHITE)?;
     
-    // Split the drawing area into a 2x2 grid
-    let areas = root.split_evenly((2, 2));
+    let ranges: Vec<Range2D> = runs.iter().map(|run| position_range(&run.data)).collect();
+    let shared = ranges.iter().skip(1).fold(ranges[0].clone(), |acc, r| {
+        (union_range(&acc.0, &r.0), union_range(&acc.1, &r.1))
+    });
+    
+    let areas = root.split_evenly((rows, cols));
     
-    for (idx, (area, result)) in areas.iter().zip(results.iter()).enumerate() {
-        // Extract positions
-        let (x_vals, y_vals): (Vec<_>, Vec<_>) = result
-            .iter()
-            .flat_map(|s| vec![s[0], s[2], s[4]])
-            .zip(result.iter().flat_map(|s| vec![s[1], s[3], s[5]]))
-            .unzip();
-
-        // Calculate ranges
-        let x_range = x_vals.iter().cloned().fold(f64::INFINITY, f64::min)
-            ..x_vals.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-        let y_range = y_vals.iter().cloned().fold(f64::INFINITY, f64::min)
-            ..y_vals.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    for ((area, run), range) in areas.iter().zip(runs.iter()).zip(ranges.iter()) {
+        let (x_range, y_range) = if shared_axes { shared.clone() } else { range.clone() };
 
         // Create chart for this section
         let mut chart = ChartBuilder::on(area)
-            .caption(format!("{} (dt = {})", method_name, dt_values[idx]), ("sans-serif", 20))
+            .caption(format!("{} (dt = {})", run.label(), run.dt), ("sans-serif", 20))
             .margin(10)
             .x_label_area_size(30)
             .y_label_area_size(30)
@@ -80,108 +161,222 @@ pub fn draw_method_comparison_grid(method_name: &str, results: &[Vec<Vec<f64>>],
 
         chart.configure_mesh().draw()?;
 
-        // Extract trajectory data
-        let mut x1 = vec![];
-        let mut y1 = vec![];
-        let mut x2 = vec![];
-        let mut y2 = vec![];
-        let mut x3 = vec![];
-        let mut y3 = vec![];
-
-        for s in result {
-            x1.push(s[0]);
-            y1.push(s[1]);
-            x2.push(s[2]);
-            y2.push(s[3]);
-            x3.push(s[4]);
-            y3.push(s[5]);
-        }
-
         // Draw trajectories
-        chart.draw_series(LineSeries::new(x1.iter().zip(y1.iter()).map(|(&x, &y)| (x, y)), &RED))?;
-        chart.draw_series(LineSeries::new(x2.iter().zip(y2.iter()).map(|(&x, &y)| (x, y)), &BLUE))?;
-        chart.draw_series(LineSeries::new(x3.iter().zip(y3.iter()).map(|(&x, &y)| (x, y)), &GREEN))?;
+        let stride = series_stride(run.data.len());
+        chart.draw_series(LineSeries::new(run.data.iter().step_by(stride).map(|s| (s[0], s[1])), &RED))?;
+        chart.draw_series(LineSeries::new(run.data.iter().step_by(stride).map(|s| (s[2], s[3])), &BLUE))?;
+        chart.draw_series(LineSeries::new(run.data.iter().step_by(stride).map(|s| (s[4], s[5])), &GREEN))?;
     }
 
     Ok(())
 }
 
-pub fn plot_energy_errors_grid(euler_results: &[Vec<Vec<f64>>], rk4_results: &[Vec<Vec<f64>>], dt_values: &[f64], filename: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let root = BitMapBackend::new(filename, (1000, 1000)).into_drawing_area();
+// Błąd energii: jeden panel na każdy krok czasowy, w panelu wszystkie metody
+// policzone z tym krokiem
+pub fn plot_energy_errors_grid(runs: &[&Run], shared_axes: bool, filename: &str) -> PlotResult {
+    // Kroki czasowe w kolejności pojawienia się
+    let mut dt_values: Vec<f64> = Vec::new();
+    for run in runs {
+        if !dt_values.contains(&run.dt) {
+            dt_values.push(run.dt);
+        }
+    }
+    
+    let (rows, cols) = grid_shape(dt_values.len());
+    let size = (cols as u32 * PANEL_SIZE, rows as u32 * PANEL_SIZE);
+    save_plot!(filename, size, |root| plot_energy_errors_grid_on(root, runs, &dt_values, (rows, cols), shared_axes))
+}
+
+fn plot_energy_errors_grid_on<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, runs: &[&Run], dt_values: &[f64], (rows, cols): (usize, usize), shared_axes: bool) -> PlotResult
+where
+    DB::ErrorType: 'static,
+{
     root.fill(&WHITE)?;
     
-    // Split the drawing area into a 2x2 grid
-    let areas = root.split_evenly((2, 2));
+    let errors: Vec<Vec<f64>> = runs.iter().map(|run| energy_errors(&run.data, run.gravity)).collect();
     
-    for (idx, (area, (euler_data, rk4_data))) in areas.iter().zip(euler_results.iter().zip(rk4_results.iter())).enumerate() {
-        // Calculate energy error for both methods
-        let euler_energy: Vec<f64> = euler_data.iter().map(|state| calculate_energy(state)).collect();
-        let rk4_energy: Vec<f64> = rk4_data.iter().map(|state| calculate_energy(state)).collect();
-        
-        // Calculate relative energy error
-        let initial_energy_euler = euler_energy[0];
-        let initial_energy_rk4 = rk4_energy[0];
-        
-        let euler_energy_error: Vec<f64> = euler_energy.iter()
-            .map(|e| (e - initial_energy_euler).abs() / initial_energy_euler.abs())
-            .collect();
-        
-        let rk4_energy_error: Vec<f64> = rk4_energy.iter()
-            .map(|e| (e - initial_energy_rk4).abs() / initial_energy_rk4.abs())
-            .collect();
-        
-        let time_points: Vec<f64> = (0..euler_energy.len()).map(|i| i as f64).collect();
-        
-        // Find the min/max error for Y axis scaling
-        let min_error = euler_energy_error.iter()
-            .chain(rk4_energy_error.iter())
-            .cloned()
-            .fold(f64::MAX, |a, b| a.min(b))
-            .max(1e-15);
-        
-        let max_error = euler_energy_error.iter()
-            .chain(rk4_energy_error.iter())
-            .cloned()
-            .fold(0.0, f64::max)
-            .max(1e-12);
+    // Find the min/max error for Y axis scaling
+    let error_range = |indices: &[usize]| {
+        let values = || indices.iter().flat_map(|&i| errors[i].iter().cloned());
+        let min_error = values().fold(f64::MAX, |a, b| a.min(b)).max(1e-15);
+        let max_error = values().fold(0.0, f64::max).max(1e-12);
+        let t_max = indices.iter().map(|&i| runs[i].times[runs[i].times.len() - 1]).fold(0.0, f64::max);
+        (min_error, max_error, t_max.max(1e-12))
+    };
+    let all_runs: Vec<usize> = (0..runs.len()).collect();
+    let shared = error_range(&all_runs);
+    
+    let areas = root.split_evenly((rows, cols));
+    
+    for (area, &dt) in areas.iter().zip(dt_values.iter()) {
+        let members: Vec<usize> = (0..runs.len()).filter(|&i| runs[i].dt == dt).collect();
+        let (min_error, max_error, t_max) = if shared_axes { shared } else { error_range(&members) };
         
         // Create chart for this section
         let mut chart = ChartBuilder::on(area)
-            .caption(format!("Błąd energii (dt = {})", dt_values[idx]), ("sans-serif", 20))
+            .caption(format!("Błąd energii (dt = {})", dt), ("sans-serif", 20))
             .margin(10)
             .x_label_area_size(30)
             .y_label_area_size(40)
             .build_cartesian_2d(
-                0.0..time_points.len() as f64,
+                0.0..t_max,
                 (min_error / 10.0..max_error * 10.0).log_scale()
             )?;
     
         chart.configure_mesh()
             .y_desc("Błąd (log)")
-            .x_desc("Krok")
+            .x_desc("Czas")
+            .y_label_formatter(&|v| format!("{:.0e}", v))
             .draw()?;
         
         // Draw energy error plots
+        for &i in &members {
+            let run = runs[i];
+            let color = run.color();
+            let points = run.times.iter()
+                .zip(errors[i].iter())
+                .step_by(series_stride(run.times.len()))
+                .map(|(&t, &e)| (t, e));
+            let series = if run.summation == Summation::Naive {
+                chart.draw_series(LineSeries::new(points, &color))?
+            } else {
+                chart.draw_series(DashedLineSeries::new(points, 6, 4, color.into()))?
+            };
+            series
+                .label(run.label())
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+        }
+        
+        // Add legend
+        chart.configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+    }
+
+    Ok(())
+}
+
+const BODY_COLORS: [RGBColor; 3] = [RED, BLUE, GREEN];
+
+fn value_range(values: impl Iterator<Item = f64>) -> std::ops::Range<f64> {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), v| (a.min(v), b.max(v)));
+    // Stała wartość - poszerzamy zakres, żeby oś miała niezerową długość
+    let pad = if max > min { 0.0 } else { min.abs().max(1.0) * 1e-3 };
+    min - pad..max + pad
+}
+
+// Raport diagnostyczny jednego przebiegu: odległości między ciałami,
+// podział energii na kinetyczną i potencjalną oraz rzuty przestrzeni fazowej
+// (x-vx i y-vy dla każdego ciała)
+pub fn draw_run_report(run: &Run, filename: &str) -> PlotResult {
+    save_plot!(filename, (3 * PANEL_SIZE, 3 * PANEL_SIZE), |root| draw_run_report_on(root, run))
+}
+
+fn draw_run_report_on<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, run: &Run) -> PlotResult
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+    let root = root.titled(&format!("{} (dt = {})", run.label(), run.dt), ("sans-serif", 30))?;
+
+    let stride = series_stride(run.data.len());
+    let states: Vec<(f64, &Vec<f64>)> = run.times.iter().cloned()
+        .zip(run.data.iter())
+        .step_by(stride)
+        .collect();
+    let t_range = 0.0..run.times[run.times.len() - 1].max(1e-12);
+
+    let (top, bottom) = root.split_vertically(PANEL_SIZE);
+    let top_areas = top.split_evenly((1, 2));
+
+    // Odległości między parami ciał w skali logarytmicznej
+    let distances: Vec<[f64; 3]> = states.iter().map(|(_, s)| pair_distances(s)).collect();
+    let d_min = distances.iter().flatten().cloned().fold(f64::INFINITY, f64::min).max(1e-12);
+    let d_max = distances.iter().flatten().cloned().fold(0.0, f64::max).max(d_min * 10.0);
+    let mut chart = ChartBuilder::on(&top_areas[0])
+        .caption("Odległości między ciałami", ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(50)
+        .build_cartesian_2d(t_range.clone(), (d_min / 2.0..d_max * 2.0).log_scale())?;
+    chart.configure_mesh()
+        .x_desc("Czas")
+        .y_desc("r (log)")
+        .y_label_formatter(&|v| format!("{:.0e}", v))
+        .draw()?;
+    for (pair, (label, color)) in [("r12", MAGENTA), ("r13", CYAN), ("r23", BLACK)].into_iter().enumerate() {
         chart.draw_series(LineSeries::new(
-            time_points.iter().zip(euler_energy_error.iter()).map(|(&t, &e)| (t, e)),
-            &RED,
+            states.iter().zip(distances.iter()).map(|((t, _), d)| (*t, d[pair])),
+            color,
         ))?
-        .label("Euler")
-        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &RED));
-        
+        .label(label)
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+    chart.configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    // Energia kinetyczna, potencjalna i całkowita. Podział na K i U jest
+    // newtonowski; całkowita pochodzi z modelu grawitacji, więc przy 1PN
+    // zawiera poprawki i nie jest sumą dwóch pozostałych krzywych
+    let energies: Vec<[f64; 3]> = states.iter()
+        .map(|(_, s)| [kinetic_energy(s), potential_energy(s), run.gravity.energy(s)])
+        .collect();
+    let e_range = value_range(energies.iter().flatten().cloned());
+    let mut chart = ChartBuilder::on(&top_areas[1])
+        .caption("Podział energii", ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(50)
+        .build_cartesian_2d(t_range, e_range)?;
+    chart.configure_mesh()
+        .x_desc("Czas")
+        .y_desc("Energia")
+        .draw()?;
+    let labels = match run.gravity {
+        Gravity::Newton => ["kinetyczna", "potencjalna", "całkowita"].map(String::from),
+        Gravity::PostNewtonian(_) => [
+            "kinetyczna (newtonowska)".to_string(),
+            "potencjalna (newtonowska)".to_string(),
+            format!("całkowita ({})", run.gravity.label()),
+        ],
+    };
+    for (part, (label, color)) in labels.into_iter().zip([RED, BLUE, BLACK]).enumerate() {
         chart.draw_series(LineSeries::new(
-            time_points.iter().zip(rk4_energy_error.iter()).map(|(&t, &e)| (t, e)),
-            &BLUE,
+            states.iter().zip(energies.iter()).map(|((t, _), e)| (*t, e[part])),
+            color,
         ))?
-        .label("RK4")
-        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &BLUE));
-        
-        // Add legend
-        chart.configure_series_labels()
-            .background_style(&WHITE.mix(0.8))
-            .border_style(&BLACK)
+        .label(label)
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+    chart.configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    // Rzuty przestrzeni fazowej: wiersz na współrzędną, kolumna na ciało
+    let phase_areas = bottom.split_evenly((2, 3));
+    for (i, area) in phase_areas.iter().enumerate() {
+        let (axis, body) = (i / 3, i % 3);
+        let (q, v) = (2 * body + axis, 6 + 2 * body + axis);
+        let name = if axis == 0 { "x" } else { "y" };
+        let mut chart = ChartBuilder::on(area)
+            .caption(format!("Ciało {}: {} - v{}", body + 1, name, name), ("sans-serif", 18))
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(40)
+            .build_cartesian_2d(
+                value_range(states.iter().map(|(_, s)| s[q])),
+                value_range(states.iter().map(|(_, s)| s[v])),
+            )?;
+        chart.configure_mesh()
+            .x_desc(name)
+            .y_desc(format!("v{}", name))
             .draw()?;
+        chart.draw_series(LineSeries::new(states.iter().map(|(_, s)| (s[q], s[v])), BODY_COLORS[body]))?;
     }
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,427 @@
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Print, SetForegroundColor, ResetColor};
+use crossterm::{cursor, execute, queue, terminal};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use crate::physics::{center_of_mass, Gravity, Integrator, Method, Summation};
+
+// Podgląd symulacji na żywo w terminalu. Położenia i ślady ciał rysujemy
+// znakami Braille'a (2x4 punkty na znak), więc rozdzielczość jest
+// kilkukrotnie większa niż liczba znaków w terminalu.
+
+const BODY_COLORS: [Color; 3] = [Color::Red, Color::Blue, Color::Green];
+const ZOOM_STEP: f64 = 1.25;
+const PAN_STEP: f64 = 0.1; // część szerokości kadru przesuwana jednym naciśnięciem
+
+pub struct WatchOptions {
+    pub method: Method,
+    pub dt: f64,
+    pub summation: Summation,
+    pub gravity: Gravity,
+    pub fps: f64,
+    pub time_per_second: f64, // jednostki czasu fizycznego na sekundę
+    pub trail_length: usize,  // liczba ostatnich kroków pozostawiających ślad
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        WatchOptions {
+            method: Method::Rk4,
+            dt: 0.001,
+            summation: Summation::Naive,
+            gravity: Gravity::Newton,
+            fps: 30.0,
+            time_per_second: 0.4,
+            trail_length: 4000,
+        }
+    }
+}
+
+// Płótno z punktami Braille'a; każdy znak pamięta kolor ostatniego ciała,
+// które w nim narysowano
+struct BrailleCanvas {
+    cols: usize,
+    rows: usize,
+    dots: Vec<u8>,
+    colors: Vec<Option<usize>>,
+}
+
+impl BrailleCanvas {
+    fn new(cols: usize, rows: usize) -> Self {
+        BrailleCanvas {
+            cols,
+            rows,
+            dots: vec![0; cols * rows],
+            colors: vec![None; cols * rows],
+        }
+    }
+
+    fn width(&self) -> i64 {
+        2 * self.cols as i64
+    }
+
+    fn height(&self) -> i64 {
+        4 * self.rows as i64
+    }
+
+    fn set(&mut self, x: i64, y: i64, body: usize) {
+        if x < 0 || y < 0 || x >= self.width() || y >= self.height() {
+            return;
+        }
+        let cell = (y / 4) as usize * self.cols + (x / 2) as usize;
+        // Numeracja punktów w znaku Braille'a: lewa kolumna 0,1,2,6, prawa 3,4,5,7
+        let bit = match (x % 2, y % 4) {
+            (0, 3) => 6,
+            (1, 3) => 7,
+            (0, dy) => dy,
+            (_, dy) => 3 + dy,
+        };
+        self.dots[cell] |= 1 << bit;
+        self.colors[cell] = Some(body);
+    }
+
+    // Odcinek algorytmem Bresenhama, po przycięciu do płótna (np. po
+    // wyrzuceniu ciała daleko końce leżą o miliony punktów poza nim)
+    fn line(&mut self, from: (i64, i64), to: (i64, i64), body: usize) {
+        let Some(((x0, y0), (x1, y1))) = clip_segment(from, to, self.width() - 1, self.height() - 1) else {
+            return;
+        };
+        let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+        let (sx, sy) = (if x0 < x1 { 1 } else { -1 }, if y0 < y1 { 1 } else { -1 });
+        let (mut x, mut y, mut err) = (x0, y0, dx + dy);
+        loop {
+            self.set(x, y, body);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+}
+
+// Przycięcie odcinka do prostokąta [0, x_max] x [0, y_max] algorytmem
+// Cohena-Sutherlanda; None, gdy odcinek leży w całości poza nim. Punkty
+// dalej niż pół punktu za brzegiem są na zewnątrz, więc błąd zaokrągleń
+// w wyznaczonym przecięciu nie przerzuca go z powrotem poza płótno.
+fn clip_segment(from: (i64, i64), to: (i64, i64), x_max: i64, y_max: i64) -> Option<((i64, i64), (i64, i64))> {
+    const LEFT: u8 = 1;
+    const RIGHT: u8 = 2;
+    const BOTTOM: u8 = 4;
+    const TOP: u8 = 8;
+    let (x_max, y_max) = (x_max as f64, y_max as f64);
+    let code = |(x, y): (f64, f64)| {
+        let mut code = 0;
+        if x < -0.5 {
+            code |= LEFT;
+        } else if x > x_max + 0.5 {
+            code |= RIGHT;
+        }
+        if y < -0.5 {
+            code |= TOP;
+        } else if y > y_max + 0.5 {
+            code |= BOTTOM;
+        }
+        code
+    };
+
+    let (mut p0, mut p1) = ((from.0 as f64, from.1 as f64), (to.0 as f64, to.1 as f64));
+    let (mut code0, mut code1) = (code(p0), code(p1));
+    loop {
+        if code0 | code1 == 0 {
+            let round = |(x, y): (f64, f64)| (x.round() as i64, y.round() as i64);
+            return Some((round(p0), round(p1)));
+        }
+        if code0 & code1 != 0 {
+            return None;
+        }
+        // Przesuwamy na brzeg koniec leżący na zewnątrz
+        let outside = if code0 != 0 { code0 } else { code1 };
+        let ((x0, y0), (x1, y1)) = (p0, p1);
+        let point = if outside & TOP != 0 {
+            (x0 + (x1 - x0) * (0.0 - y0) / (y1 - y0), 0.0)
+        } else if outside & BOTTOM != 0 {
+            (x0 + (x1 - x0) * (y_max - y0) / (y1 - y0), y_max)
+        } else if outside & RIGHT != 0 {
+            (x_max, y0 + (y1 - y0) * (x_max - x0) / (x1 - x0))
+        } else {
+            (0.0, y0 + (y1 - y0) * (0.0 - x0) / (x1 - x0))
+        };
+        if outside == code0 {
+            p0 = point;
+            code0 = code(p0);
+        } else {
+            p1 = point;
+            code1 = code(p1);
+        }
+    }
+}
+
+// Kadr: środek i połowa szerokości w jednostkach fizycznych
+struct View {
+    cx: f64,
+    cy: f64,
+    half_width: f64,
+}
+
+impl View {
+    // Punkty Braille'a są w przybliżeniu kwadratowe, więc skala jest ta sama w obu osiach
+    fn to_dots(&self, canvas: &BrailleCanvas, x: f64, y: f64) -> (i64, i64) {
+        let scale = canvas.width() as f64 / (2.0 * self.half_width);
+        let px = (x - self.cx) * scale + canvas.width() as f64 / 2.0;
+        let py = canvas.height() as f64 / 2.0 - (y - self.cy) * scale;
+        // Ograniczenie chroni przed przepełnieniem dla bardzo odległych ciał
+        let clamp = |v: f64| v.clamp(-1e9, 1e9) as i64;
+        (clamp(px), clamp(py))
+    }
+}
+
+// Przywraca terminal do normalnego stanu także przy błędzie
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), ResetColor, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// Reakcja na klawisz
+enum Action {
+    Quit,
+    TogglePause,
+    Zoom(f64),
+    Pan(f64, f64),
+    Reset,
+    None,
+}
+
+fn key_action(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+        KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+        KeyCode::Char(' ') | KeyCode::Char('p') => Action::TogglePause,
+        KeyCode::Char('+') | KeyCode::Char('=') => Action::Zoom(ZOOM_STEP),
+        KeyCode::Char('-') => Action::Zoom(1.0 / ZOOM_STEP),
+        KeyCode::Left => Action::Pan(-PAN_STEP, 0.0),
+        KeyCode::Right => Action::Pan(PAN_STEP, 0.0),
+        KeyCode::Up => Action::Pan(0.0, PAN_STEP),
+        KeyCode::Down => Action::Pan(0.0, -PAN_STEP),
+        KeyCode::Char('0') => Action::Reset,
+        _ => Action::None,
+    }
+}
+
+// Uruchamia symulację i rysuje ją w terminalu aż do naciśnięcia `q`
+pub fn watch(y0: Vec<f64>, options: &WatchOptions) -> Result<(), Box<dyn Error>> {
+    let gravity = options.gravity;
+    let force = |y: &[f64], t: f64| gravity.derivative(y, t);
+    let mut integrator = Integrator::new(options.method, force, y0.clone(), 0.0, options.dt, options.summation);
+    let initial_energy = gravity.energy(&y0);
+
+    // Kadr początkowy: środek masy i dwukrotność największej odległości od niego
+    let (com_x, com_y, _, _) = center_of_mass(&y0);
+    let radius = (0..3)
+        .map(|body| (y0[2 * body] - com_x).hypot(y0[2 * body + 1] - com_y))
+        .fold(0.0, f64::max)
+        .max(1e-3);
+    let initial_view = || View { cx: com_x, cy: com_y, half_width: 2.0 * radius };
+    let mut view = initial_view();
+
+    let mut trails: [VecDeque<(f64, f64)>; 3] = Default::default();
+    let push_trails = |trails: &mut [VecDeque<(f64, f64)>; 3], state: &[f64]| {
+        for (body, trail) in trails.iter_mut().enumerate() {
+            trail.push_back((state[2 * body], state[2 * body + 1]));
+            if trail.len() > options.trail_length {
+                trail.pop_front();
+            }
+        }
+    };
+    push_trails(&mut trails, &y0);
+
+    let frame_time = Duration::from_secs_f64(1.0 / options.fps);
+    let time_per_frame = options.time_per_second / options.fps;
+    let mut target_time = 0.0;
+    let mut paused = false;
+
+    let _guard = TerminalGuard::new()?;
+    let mut stdout = io::stdout();
+    let mut last_size = None;
+
+    loop {
+        let frame_start = Instant::now();
+
+        if !paused {
+            target_time += time_per_frame;
+            while integrator.time() + options.dt / 2.0 <= target_time {
+                let state = integrator.step();
+                push_trails(&mut trails, state);
+            }
+        }
+
+        let (cols, rows) = terminal::size()?;
+        // Pierwszy wiersz to stan symulacji, ostatni - opis klawiszy
+        let canvas_rows = (rows as usize).saturating_sub(2).max(1);
+        let mut canvas = BrailleCanvas::new(cols as usize, canvas_rows);
+        for (body, trail) in trails.iter().enumerate() {
+            let mut previous = None;
+            for &(x, y) in trail {
+                let point = view.to_dots(&canvas, x, y);
+                match previous {
+                    Some(p) if p != point => canvas.line(p, point, body),
+                    Some(_) => {}
+                    None => canvas.set(point.0, point.1, body),
+                }
+                previous = Some(point);
+            }
+        }
+
+        // Same ciała jako pełne znaki na wierzchu śladów
+        let state = integrator.state();
+        let markers: Vec<Option<(usize, usize)>> = (0..3)
+            .map(|body| {
+                let (px, py) = view.to_dots(&canvas, state[2 * body], state[2 * body + 1]);
+                let inside = px >= 0 && py >= 0 && px < canvas.width() && py < canvas.height();
+                inside.then_some(((px / 2) as usize, (py / 4) as usize))
+            })
+            .collect();
+
+        let energy_error = (gravity.energy(state) - initial_energy).abs() / initial_energy.abs();
+        let method = match options.summation {
+            Summation::Naive => options.method.name().to_string(),
+            summation => format!("{} ({})", options.method.name(), summation.label()),
+        };
+        let method = match gravity {
+            Gravity::Newton => method,
+            gravity => format!("{} [{}]", method, gravity.label()),
+        };
+        let status = format!(
+            " t = {:.3}  dt = {}  {}  |ΔE/E0| = {:.3e}  zoom {:.2}x{}",
+            integrator.time(),
+            options.dt,
+            method,
+            energy_error,
+            2.0 * radius / view.half_width,
+            if paused { "  [PAUZA]" } else { "" },
+        );
+
+        // Cały ekran czyścimy tylko po zmianie rozmiaru, żeby obraz nie migotał
+        if last_size != Some((cols, rows)) {
+            queue!(stdout, terminal::Clear(terminal::ClearType::All))?;
+            last_size = Some((cols, rows));
+        }
+        queue!(stdout, cursor::MoveTo(0, 0), ResetColor, Print(truncate(&status, cols as usize)),
+               terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        for row in 0..canvas.rows {
+            queue!(stdout, cursor::MoveTo(0, row as u16 + 1))?;
+            let mut current: Option<Color> = None;
+            let mut line = String::new();
+            for col in 0..canvas.cols {
+                let cell = row * canvas.cols + col;
+                let marker = markers.iter().position(|m| *m == Some((col, row)));
+                let (ch, color) = match marker {
+                    Some(body) => ('●', Some(BODY_COLORS[body])),
+                    None if canvas.dots[cell] != 0 => (
+                        char::from_u32(0x2800 + canvas.dots[cell] as u32).unwrap_or(' '),
+                        canvas.colors[cell].map(|body| BODY_COLORS[body]),
+                    ),
+                    None => (' ', None),
+                };
+                // Kolor zmieniamy tylko tam, gdzie jest potrzebny
+                if ch != ' ' && color != current {
+                    queue!(stdout, Print(std::mem::take(&mut line)))?;
+                    if let Some(color) = color {
+                        queue!(stdout, SetForegroundColor(color))?;
+                    }
+                    current = color;
+                }
+                line.push(ch);
+            }
+            queue!(stdout, Print(line), ResetColor)?;
+        }
+        let help = " spacja: pauza  +/-: zoom  strzałki: przesuwanie  0: reset kadru  q: wyjście";
+        queue!(stdout, cursor::MoveTo(0, rows.saturating_sub(1)), Print(truncate(help, cols as usize)),
+               terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        stdout.flush()?;
+
+        // Klawisze obsługujemy do końca czasu przeznaczonego na klatkę
+        loop {
+            let remaining = frame_time.saturating_sub(frame_start.elapsed());
+            if !event::poll(remaining)? {
+                break;
+            }
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key_action(key) {
+                Action::Quit => return Ok(()),
+                Action::TogglePause => paused = !paused,
+                Action::Zoom(factor) => view.half_width /= factor,
+                Action::Pan(dx, dy) => {
+                    view.cx += dx * 2.0 * view.half_width;
+                    view.cy += dy * 2.0 * view.half_width;
+                }
+                Action::Reset => view = initial_view(),
+                Action::None => {}
+            }
+        }
+    }
+}
+
+// Obcina tekst do szerokości terminala (licząc znaki, nie bajty)
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_outside_canvas_is_dropped() {
+        assert_eq!(clip_segment((-50, -10), (-5, 30), 99, 39), None);
+        assert_eq!(clip_segment((120, 50), (1_000_000_000, 60), 99, 39), None);
+        // Przechodzi obok rogu, ale nie przez płótno
+        assert_eq!(clip_segment((90, -30), (130, 10), 99, 39), None);
+    }
+
+    #[test]
+    fn segment_crossing_corner_is_clipped_to_canvas() {
+        let clipped = clip_segment((-1_000_000_000, -1_000_000_000), (1_000_000_000, 1_000_000_000), 99, 39);
+        assert_eq!(clipped, Some(((0, 0), (39, 39))));
+        let clipped = clip_segment((-10, 49), (10, 29), 99, 39);
+        assert_eq!(clipped, Some(((0, 39), (10, 29))));
+    }
+
+    #[test]
+    fn segment_inside_canvas_is_unchanged() {
+        assert_eq!(clip_segment((3, 4), (97, 38), 99, 39), Some(((3, 4), (97, 38))));
+        assert_eq!(clip_segment((0, 0), (99, 39), 99, 39), Some(((0, 0), (99, 39))));
+    }
+
+    #[test]
+    fn far_segment_draws_only_canvas_dots() {
+        let mut canvas = BrailleCanvas::new(10, 5);
+        canvas.line((-1_000_000_000, 10), (1_000_000_000, 10), 0);
+        let lit: u32 = canvas.dots.iter().map(|d| d.count_ones()).sum();
+        assert_eq!(lit as i64, canvas.width());
+    }
+}
@@ -0,0 +1,82 @@
+use image::{Rgb, RgbImage};
+use num_complex::Complex;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use crate::iteration::{ComplexFunction, Iteration};
+use crate::palette::Coloring;
+use crate::{classify_point, pixel_to_complex, render_fractal, FractalConfig};
+
+// Liczba powtórzeń każdego pomiaru; bierzemy najlepszy czas
+const REPEATS: usize = 3;
+
+// Schemat synchronizacji poprzedniej wersji renderera: wektor współrzędnych
+// wszystkich pikseli, a przy każdym pikselu blokada wspólnej mapy kolorów
+// i blokada obrazu. To nie jest dawny kod - punkty klasyfikuje bieżące
+// `classify_point` (środki pikseli, bieżące kryteria stopu), więc pomiar
+// pokazuje wyłącznie koszt blokad, a oba obrazy muszą być identyczne.
+fn render_with_locks<F>(function: &F, roots: &[Complex<f64>], config: &FractalConfig) -> (RgbImage, usize)
+where
+    F: ComplexFunction,
+{
+    let (width, height) = (config.width, config.height);
+    let img = Arc::new(Mutex::new(RgbImage::new(width, height)));
+    let roots_map = Arc::new(Mutex::new(HashMap::<Option<usize>, Rgb<u8>>::new()));
+    let converged_count = Arc::new(AtomicUsize::new(0));
+    let coloring = Coloring::default();
+
+    let pixels: Vec<(u32, u32)> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (y, x)))
+        .collect();
+
+    pixels.par_iter().for_each(|&(i, j)| {
+        let root = classify_point(function, Iteration::Newton, roots, pixel_to_complex::<f64>(config, j, i), config).root;
+        if root.is_some() {
+            converged_count.fetch_add(1, Ordering::Relaxed);
+        }
+        let color = *roots_map.lock().unwrap().entry(root).or_insert_with(|| coloring.color(root, 0.0, roots, config.max_iter));
+        img.lock().unwrap().put_pixel(j, i, color);
+    });
+
+    let img = Arc::try_unwrap(img).unwrap().into_inner().unwrap();
+    (img, converged_count.load(Ordering::Relaxed))
+}
+
+fn best_time(mut run: impl FnMut() -> (RgbImage, usize)) -> (Duration, RgbImage) {
+    let mut best = Duration::MAX;
+    let mut image = None;
+    for _ in 0..REPEATS {
+        let start = Instant::now();
+        let (img, _) = run();
+        best = best.min(start.elapsed());
+        image = Some(img);
+    }
+    (best, image.unwrap())
+}
+
+// Mierzy oba renderery dla kwadratowych obrazów o podanych rozmiarach; błąd,
+// gdy dadzą różne obrazy
+pub fn run_benchmark<F>(function: &F, roots: &[Complex<f64>], config: &FractalConfig, sizes: &[u32]) -> Result<(), String>
+where
+    F: ComplexFunction,
+{
+    println!("Koszt blokad przy każdym pikselu (ta sama metoda klasyfikacji w obu rendererach)");
+    println!("Wątki rayon: {}, najlepszy z {} pomiarów", rayon::current_num_threads(), REPEATS);
+    println!("{:>10} {:>14} {:>14} {:>10}", "rozmiar", "z blokadami", "bez blokad", "przyspiesz.");
+    for &size in sizes {
+        let config = FractalConfig { width: size, height: size, ..*config };
+        let (locked, locked_img) = best_time(|| render_with_locks(function, roots, &config));
+        let (tiled, tiled_img) = best_time(|| {
+            let (img, map) = render_fractal(function, Iteration::Newton, roots, &config, &Coloring::default());
+            (img, map.converged())
+        });
+        if locked_img != tiled_img {
+            return Err(format!("Renderery dały różne obrazy dla rozmiaru {}", size));
+        }
+        println!("{:>10} {:>14.3?} {:>14.3?} {:>9.2}x",
+                 format!("{}x{}", size, size), locked, tiled, locked.as_secs_f64() / tiled.as_secs_f64());
+    }
+    Ok(())
+}
@@ -0,0 +1,423 @@
+use num_complex::Complex;
+use num_traits::{Num, One, Zero};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::str::FromStr;
+
+// Liczba double-double: nieobliczona suma hi + lo dwóch liczb f64, przy czym
+// |lo| <= ulp(hi) / 2. Daje ok. 106 bitów mantysy (ok. 32 cyfry dziesiętne)
+// przy zakresie wykładnika f64. Algorytmy wg Hidy, Li i Baileya (biblioteka QD).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DoubleDouble {
+    pub hi: f64,
+    pub lo: f64,
+}
+
+// Dokładna suma: s + e == a + b
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+// Jak two_sum, ale wymaga |a| >= |b|
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+// Dokładny iloczyn: p + e == a * b (błąd z fused multiply-add)
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, a.mul_add(b, -p))
+}
+
+impl DoubleDouble {
+    pub const fn new(x: f64) -> DoubleDouble {
+        DoubleDouble { hi: x, lo: 0.0 }
+    }
+
+    fn renormalize(hi: f64, lo: f64) -> DoubleDouble {
+        let (hi, lo) = quick_two_sum(hi, lo);
+        DoubleDouble { hi, lo }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.hi + self.lo
+    }
+
+    pub fn is_finite(self) -> bool {
+        self.hi.is_finite()
+    }
+
+    pub fn abs(self) -> DoubleDouble {
+        if self.hi < 0.0 { -self } else { self }
+    }
+
+    pub fn floor(self) -> DoubleDouble {
+        let hi = self.hi.floor();
+        if hi != self.hi {
+            return DoubleDouble::new(hi);
+        }
+        // hi jest całkowite, zaokrąglamy część lo
+        DoubleDouble::renormalize(hi, self.lo.floor())
+    }
+
+    // 10^n liczone przez podnoszenie do kwadratu
+    pub fn powi10(n: i32) -> DoubleDouble {
+        let mut result = DoubleDouble::new(1.0);
+        let mut base = DoubleDouble::new(10.0);
+        let mut k = n.unsigned_abs();
+        while k > 0 {
+            if k & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            k >>= 1;
+        }
+        if n < 0 { DoubleDouble::new(1.0) / result } else { result }
+    }
+
+    // Cyfry dziesiętne i wykładnik: x = 0.d1 d2 d3 ... * 10^(exponent + 1),
+    // czyli pierwsza cyfra stoi przy 10^exponent. Ostatnia cyfra jest
+    // zaokrąglona.
+    fn digits(self, count: usize) -> (Vec<u8>, i32) {
+        let x = self.abs();
+        if x.hi == 0.0 {
+            return (vec![0; count], 0);
+        }
+        let mut exponent = x.hi.log10().floor() as i32;
+        let mut r = x / DoubleDouble::powi10(exponent);
+        // log10 z f64 może się pomylić o jeden przy granicy dekady
+        if r.hi >= 10.0 {
+            exponent += 1;
+            r = r / DoubleDouble::new(10.0);
+        } else if r.hi < 1.0 {
+            exponent -= 1;
+            r = r * DoubleDouble::new(10.0);
+        }
+
+        let mut digits = Vec::with_capacity(count + 1);
+        for _ in 0..=count {
+            let d = r.hi.floor().clamp(0.0, 9.0);
+            digits.push(d as u8);
+            r = (r - DoubleDouble::new(d)) * DoubleDouble::new(10.0);
+        }
+        // Zaokrąglenie wg cyfry nadmiarowej, z przeniesieniem
+        if digits.pop().unwrap() >= 5 {
+            let mut k = count;
+            loop {
+                if k == 0 {
+                    digits.insert(0, 1);
+                    digits.pop();
+                    exponent += 1;
+                    break;
+                }
+                k -= 1;
+                if digits[k] == 9 {
+                    digits[k] = 0;
+                } else {
+                    digits[k] += 1;
+                    break;
+                }
+            }
+        }
+        (digits, exponent)
+    }
+}
+
+impl From<f64> for DoubleDouble {
+    fn from(x: f64) -> DoubleDouble {
+        DoubleDouble::new(x)
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = DoubleDouble;
+    fn add(self, other: DoubleDouble) -> DoubleDouble {
+        let (s, e) = two_sum(self.hi, other.hi);
+        let (t, f) = two_sum(self.lo, other.lo);
+        let (s, e) = quick_two_sum(s, e + t);
+        DoubleDouble::renormalize(s, e + f)
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = DoubleDouble;
+    fn neg(self) -> DoubleDouble {
+        DoubleDouble { hi: -self.hi, lo: -self.lo }
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = DoubleDouble;
+    fn sub(self, other: DoubleDouble) -> DoubleDouble {
+        self + (-other)
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = DoubleDouble;
+    fn mul(self, other: DoubleDouble) -> DoubleDouble {
+        let (p, e) = two_prod(self.hi, other.hi);
+        DoubleDouble::renormalize(p, e + (self.hi * other.lo + self.lo * other.hi))
+    }
+}
+
+impl Div for DoubleDouble {
+    type Output = DoubleDouble;
+    // Trzy kroki dzielenia "szkolnego" na cyfrach f64
+    fn div(self, other: DoubleDouble) -> DoubleDouble {
+        let q1 = self.hi / other.hi;
+        let r = self - other * DoubleDouble::new(q1);
+        let q2 = r.hi / other.hi;
+        let r = r - other * DoubleDouble::new(q2);
+        let q3 = r.hi / other.hi;
+        DoubleDouble::renormalize(q1, q2) + DoubleDouble::new(q3)
+    }
+}
+
+impl Rem for DoubleDouble {
+    type Output = DoubleDouble;
+    fn rem(self, other: DoubleDouble) -> DoubleDouble {
+        let q = self / other;
+        let q = if q.hi < 0.0 { -(-q).floor() } else { q.floor() };
+        self - other * q
+    }
+}
+
+impl Zero for DoubleDouble {
+    fn zero() -> DoubleDouble {
+        DoubleDouble::new(0.0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.hi == 0.0
+    }
+}
+
+impl One for DoubleDouble {
+    fn one() -> DoubleDouble {
+        DoubleDouble::new(1.0)
+    }
+}
+
+// Complex<T> wymaga Num do działań arytmetycznych
+impl Num for DoubleDouble {
+    type FromStrRadixErr = String;
+
+    fn from_str_radix(text: &str, radix: u32) -> Result<DoubleDouble, String> {
+        if radix != 10 {
+            return Err(format!("Nieobsługiwana podstawa {}", radix));
+        }
+        text.parse()
+    }
+}
+
+// Zapis dziesiętny, np. "-0.79370052598409973737585281963615" albo "1.5e-20";
+// cyfry poza precyzją double-double nie zmieniają wyniku
+impl FromStr for DoubleDouble {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<DoubleDouble, String> {
+        let invalid = || format!("Niepoprawna liczba: '{}'", text);
+        let trimmed = text.trim();
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(pos) => (&unsigned[..pos], unsigned[pos + 1..].parse::<i32>().map_err(|_| invalid())?),
+            None => (unsigned, 0),
+        };
+
+        let mut value = DoubleDouble::new(0.0);
+        let mut fraction_digits = 0;
+        let mut seen_point = false;
+        let mut seen_digit = false;
+        for c in mantissa.chars() {
+            match c {
+                '.' if !seen_point => seen_point = true,
+                '0'..='9' => {
+                    seen_digit = true;
+                    let digit = c.to_digit(10).unwrap() as f64;
+                    value = value * DoubleDouble::new(10.0) + DoubleDouble::new(digit);
+                    if seen_point {
+                        fraction_digits += 1;
+                    }
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        if !seen_digit {
+            return Err(invalid());
+        }
+
+        let scale = exponent - fraction_digits;
+        let value = match scale.cmp(&0) {
+            Ordering::Less => value / DoubleDouble::powi10(-scale),
+            Ordering::Greater => value * DoubleDouble::powi10(scale),
+            Ordering::Equal => value,
+        };
+        Ok(if negative { -value } else { value })
+    }
+}
+
+// Bez podanej precyzji: 32 cyfry znaczące bez końcowych zer; z precyzją
+// ({:.20}): tyle cyfr po przecinku. Zapis wykładniczy dla bardzo małych
+// i dużych liczb.
+impl fmt::Display for DoubleDouble {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.is_finite() {
+            return write!(f, "{}", self.hi);
+        }
+        let sign = if self.hi < 0.0 { "-" } else if f.sign_plus() { "+" } else { "" };
+        let (_, exponent) = self.digits(1);
+        let fixed = (-5..21).contains(&exponent);
+
+        // Końcowe zera części ułamkowej (razem z kropką) pomijamy, o ile
+        // precyzja nie jest podana
+        let trim = |fraction: &str| match f.precision() {
+            None => {
+                let fraction = fraction.trim_end_matches('0');
+                if fraction.is_empty() { String::new() } else { format!(".{}", fraction) }
+            }
+            Some(_) => format!(".{}", fraction),
+        };
+
+        let mut text = String::from(sign);
+        if fixed {
+            let fraction = f.precision().unwrap_or((31 - exponent).max(0) as usize);
+            let count = (exponent + 1 + fraction as i32).max(1) as usize;
+            let (digits, exponent) = self.digits(count);
+            let digits: String = digits.iter().map(|d| char::from(b'0' + d)).collect();
+            if exponent < 0 {
+                let zeros = "0".repeat((-exponent - 1) as usize);
+                let all = format!("{}{}", zeros, digits);
+                text += &format!("0{}", trim(&all[..fraction.min(all.len())]));
+            } else {
+                let (int, frac) = digits.split_at((exponent + 1) as usize);
+                text += int;
+                if fraction > 0 {
+                    text += &trim(&frac[..fraction.min(frac.len())]);
+                }
+            }
+        } else {
+            let count = f.precision().map_or(32, |p| p + 1);
+            let (digits, exponent) = self.digits(count);
+            let digits: String = digits.iter().map(|d| char::from(b'0' + d)).collect();
+            text += &format!("{}{}e{}", &digits[..1], trim(&digits[1..]), exponent);
+        }
+        f.write_str(&text)
+    }
+}
+
+// Liczba zespolona w zapisie "a", "bi", "a+bi" albo "a-bi" (np.
+// "-0.7937005259840997373758528196361+1e-20i")
+pub fn parse_complex(text: &str) -> Result<Complex<DoubleDouble>, String> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let Some(body) = text.strip_suffix(['i', 'j']) else {
+        return Ok(Complex::new(text.parse()?, DoubleDouble::new(0.0)));
+    };
+    // Znak rozdzielający część rzeczywistą i urojoną: ostatni + albo -,
+    // który nie stoi na początku ani zaraz po wykładniku
+    let bytes = body.as_bytes();
+    let split = (1..bytes.len())
+        .rev()
+        .find(|&k| (bytes[k] == b'+' || bytes[k] == b'-') && !matches!(bytes[k - 1], b'e' | b'E'));
+    let imaginary = |part: &str| match part {
+        "" | "+" => Ok(DoubleDouble::new(1.0)),
+        "-" => Ok(DoubleDouble::new(-1.0)),
+        _ => part.parse(),
+    };
+    match split {
+        Some(k) => Ok(Complex::new(body[..k].parse()?, imaginary(&body[k..])?)),
+        None => Ok(Complex::new(DoubleDouble::new(0.0), imaginary(body)?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dd(text: &str) -> DoubleDouble {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn error_free_transformations() {
+        assert_eq!(two_sum(1.0, 1e-20), (1.0, 1e-20));
+        assert_eq!(two_sum(1e16, 1.0), (1e16, 1.0));
+        // (1 + 2^-30)^2 = 1 + 2^-29 + 2^-60
+        let a = 1.0 + 2f64.powi(-30);
+        assert_eq!(two_prod(a, a), (1.0 + 2f64.powi(-29), 2f64.powi(-60)));
+    }
+
+    #[test]
+    fn keeps_bits_lost_in_f64() {
+        let one = DoubleDouble::new(1.0);
+        let tiny = DoubleDouble::new(1e-20);
+        assert_eq!(one + tiny - one, tiny);
+        assert_eq!((1.0 + 1e-20) - 1.0, 0.0);
+
+        let third = one / DoubleDouble::new(3.0);
+        assert!((third * DoubleDouble::new(3.0) - one).abs().to_f64() < 1e-31);
+        assert_eq!(third.hi, 1.0 / 3.0);
+        assert!(third.lo != 0.0 && third.lo.abs() <= f64::EPSILON * third.hi);
+        assert_eq!(DoubleDouble::new(7.5) % DoubleDouble::new(2.0), DoubleDouble::new(1.5));
+        assert_eq!(DoubleDouble::powi10(-3), one / DoubleDouble::new(1000.0));
+    }
+
+    #[test]
+    fn parses_decimal_text() {
+        // 0.1 w f64 to 0.1000000000000000055511151231257827...
+        let tenth = dd("0.1");
+        assert_eq!(tenth.hi, 0.1);
+        assert!((tenth.lo + 5.551115123125783e-18).abs() < 1e-30);
+        assert_eq!(dd("-2"), DoubleDouble::new(-2.0));
+        assert_eq!(dd("+3.25"), DoubleDouble::new(3.25));
+        assert_eq!(dd(".5"), DoubleDouble::new(0.5));
+        assert_eq!(dd("1e3"), DoubleDouble::new(1000.0));
+        assert_eq!(dd("1.5e-20").hi, 1.5e-20);
+        for text in ["", "-", "1.2.3", "e5", "1e", "abc", "--1", "1,5"] {
+            assert!(text.parse::<DoubleDouble>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn formats_decimal_text() {
+        assert_eq!(dd("0.1").to_string(), "0.1");
+        assert_eq!(DoubleDouble::new(0.0).to_string(), "0");
+        assert_eq!(DoubleDouble::new(-2.0).to_string(), "-2");
+        assert_eq!(dd("1.5e-20").to_string(), "1.5e-20");
+        assert_eq!(dd("1e25").to_string(), "1e25");
+        // f64 najbliższa 10^25 nie jest jej równa
+        assert_eq!(DoubleDouble::new(1e25).to_string(), "1.0000000000000000905969664e25");
+        assert_eq!(format!("{:.5}", DoubleDouble::new(2.5)), "2.50000");
+        assert_eq!(format!("{:+}", DoubleDouble::new(0.25)), "+0.25");
+        let third = DoubleDouble::new(1.0) / DoubleDouble::new(3.0);
+        assert_eq!(third.to_string(), "0.33333333333333333333333333333333");
+        assert_eq!(DoubleDouble::new(f64::INFINITY).to_string(), "inf");
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for text in ["-0.7937005259840997373758528196361", "123456789.12345678901234567890123", "3.14159265358979323846264338327950e-15"] {
+            let value = dd(text);
+            let back = dd(&value.to_string());
+            assert!((back - value).abs().to_f64() <= 1e-31 * value.abs().to_f64(), "{} -> {}", text, value);
+        }
+    }
+
+    #[test]
+    fn parses_complex_numbers() {
+        let z = parse_complex("-0.7937005259840997373758528196361+1e-20i").unwrap();
+        assert_eq!(z.re, dd("-0.7937005259840997373758528196361"));
+        assert_eq!(z.im, dd("1e-20"));
+        assert_eq!(parse_complex("2.5e-3-4i").unwrap(), Complex::new(dd("2.5e-3"), DoubleDouble::new(-4.0)));
+        assert_eq!(parse_complex("-i").unwrap(), Complex::new(DoubleDouble::new(0.0), DoubleDouble::new(-1.0)));
+        assert_eq!(parse_complex("3").unwrap(), Complex::new(DoubleDouble::new(3.0), DoubleDouble::new(0.0)));
+        assert!(parse_complex("1+2x").is_err());
+    }
+}
@@ -0,0 +1,683 @@
+use num_complex::Complex;
+use crate::iteration::ComplexFunction;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// Liczby, na których obliczamy wyrażenie. Funkcję elementarną g składamy
+// z liczbą, znając g i jej pochodne w punkcie: [g(u), g'(u), g''(u), g'''(u)].
+trait Scalar: Copy + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self> {
+    fn constant(value: Complex<f64>) -> Self;
+    fn value(&self) -> Complex<f64>;
+    fn compose(self, g: [Complex<f64>; 4]) -> Self;
+}
+
+// Liczba dualna nad liczbami zespolonymi: wartość i pochodna względem z.
+// Obliczenie wyrażenia na liczbach dualnych daje jednocześnie f(z) i f'(z)
+// (różniczkowanie automatyczne w przód).
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Dual {
+    value: Complex<f64>,
+    derivative: Complex<f64>,
+}
+
+impl Scalar for Dual {
+    fn constant(value: Complex<f64>) -> Dual {
+        Dual { value, derivative: Complex::new(0.0, 0.0) }
+    }
+
+    fn value(&self) -> Complex<f64> {
+        self.value
+    }
+
+    // (g(u), g'(u) u')
+    fn compose(self, g: [Complex<f64>; 4]) -> Dual {
+        Dual { value: g[0], derivative: g[1] * self.derivative }
+    }
+}
+
+impl Add for Dual {
+    type Output = Dual;
+    fn add(self, other: Dual) -> Dual {
+        Dual { value: self.value + other.value, derivative: self.derivative + other.derivative }
+    }
+}
+
+impl Sub for Dual {
+    type Output = Dual;
+    fn sub(self, other: Dual) -> Dual {
+        Dual { value: self.value - other.value, derivative: self.derivative - other.derivative }
+    }
+}
+
+impl Mul for Dual {
+    type Output = Dual;
+    fn mul(self, other: Dual) -> Dual {
+        Dual {
+            value: self.value * other.value,
+            derivative: self.derivative * other.value + self.value * other.derivative,
+        }
+    }
+}
+
+impl Div for Dual {
+    type Output = Dual;
+    fn div(self, other: Dual) -> Dual {
+        Dual {
+            value: self.value / other.value,
+            derivative: (self.derivative * other.value - self.value * other.derivative) / (other.value * other.value),
+        }
+    }
+}
+
+impl Neg for Dual {
+    type Output = Dual;
+    fn neg(self) -> Dual {
+        Dual { value: -self.value, derivative: -self.derivative }
+    }
+}
+
+// Obcięty szereg Taylora f(z + h) = a0 + a1 h + a2 h² + a3 h³ - to samo co
+// liczba dualna, ale z pochodnymi do trzeciego rzędu (dla metod Halleya,
+// Householdera itp.)
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Jet([Complex<f64>; 4]);
+
+impl Jet {
+    fn variable(value: Complex<f64>) -> Jet {
+        let zero = Complex::new(0.0, 0.0);
+        Jet([value, Complex::new(1.0, 0.0), zero, zero])
+    }
+}
+
+impl Scalar for Jet {
+    fn constant(value: Complex<f64>) -> Jet {
+        let zero = Complex::new(0.0, 0.0);
+        Jet([value, zero, zero, zero])
+    }
+
+    fn value(&self) -> Complex<f64> {
+        self.0[0]
+    }
+
+    // g(u0 + d) = g + g' d + g'' d²/2 + g''' d³/6, gdzie d = a1 h + a2 h² + a3 h³
+    fn compose(self, g: [Complex<f64>; 4]) -> Jet {
+        let [_, a1, a2, a3] = self.0;
+        Jet([
+            g[0],
+            g[1] * a1,
+            g[1] * a2 + g[2] * a1 * a1 / 2.0,
+            g[1] * a3 + g[2] * a1 * a2 + g[3] * a1 * a1 * a1 / 6.0,
+        ])
+    }
+}
+
+impl Add for Jet {
+    type Output = Jet;
+    fn add(self, other: Jet) -> Jet {
+        Jet(std::array::from_fn(|k| self.0[k] + other.0[k]))
+    }
+}
+
+impl Sub for Jet {
+    type Output = Jet;
+    fn sub(self, other: Jet) -> Jet {
+        Jet(std::array::from_fn(|k| self.0[k] - other.0[k]))
+    }
+}
+
+impl Mul for Jet {
+    type Output = Jet;
+    fn mul(self, other: Jet) -> Jet {
+        let (a, b) = (self.0, other.0);
+        Jet(std::array::from_fn(|k| (0..=k).map(|i| a[i] * b[k - i]).sum()))
+    }
+}
+
+impl Div for Jet {
+    type Output = Jet;
+    fn div(self, other: Jet) -> Jet {
+        let (a, b) = (self.0, other.0);
+        let mut c = [Complex::new(0.0, 0.0); 4];
+        for k in 0..4 {
+            let known: Complex<f64> = (1..=k).map(|i| b[i] * c[k - i]).sum();
+            c[k] = (a[k] - known) / b[0];
+        }
+        Jet(c)
+    }
+}
+
+impl Neg for Jet {
+    type Output = Jet;
+    fn neg(self) -> Jet {
+        Jet(self.0.map(|a| -a))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Exp,
+    Log,
+    Sqrt,
+    Sinh,
+    Cosh,
+    Tanh,
+}
+
+impl Function {
+    fn parse(name: &str) -> Option<Function> {
+        match name {
+            "sin" => Some(Function::Sin),
+            "cos" => Some(Function::Cos),
+            "tan" => Some(Function::Tan),
+            "exp" => Some(Function::Exp),
+            "log" | "ln" => Some(Function::Log),
+            "sqrt" => Some(Function::Sqrt),
+            "sinh" => Some(Function::Sinh),
+            "cosh" => Some(Function::Cosh),
+            "tanh" => Some(Function::Tanh),
+            _ => None,
+        }
+    }
+
+    // Wartość funkcji i jej trzech pierwszych pochodnych w punkcie u
+    fn derivatives(&self, u: Complex<f64>) -> [Complex<f64>; 4] {
+        let one = Complex::new(1.0, 0.0);
+        match self {
+            Function::Sin => {
+                let (sin, cos) = (u.sin(), u.cos());
+                [sin, cos, -sin, -cos]
+            }
+            Function::Cos => {
+                let (sin, cos) = (u.sin(), u.cos());
+                [cos, -sin, -cos, sin]
+            }
+            Function::Tan => {
+                let tan = u.tan();
+                let q = one + tan * tan;
+                [tan, q, 2.0 * tan * q, 2.0 * q * (one + 3.0 * tan * tan)]
+            }
+            Function::Exp => [u.exp(); 4],
+            Function::Log => {
+                let r = one / u;
+                [u.ln(), r, -r * r, 2.0 * r * r * r]
+            }
+            Function::Sqrt => {
+                let sqrt = u.sqrt();
+                let r = one / sqrt;
+                [sqrt, 0.5 * r, -0.25 * r * r * r, 0.375 * r * r * r * r * r]
+            }
+            Function::Sinh => {
+                let (sinh, cosh) = (u.sinh(), u.cosh());
+                [sinh, cosh, sinh, cosh]
+            }
+            Function::Cosh => {
+                let (sinh, cosh) = (u.sinh(), u.cosh());
+                [cosh, sinh, cosh, sinh]
+            }
+            Function::Tanh => {
+                let tanh = u.tanh();
+                let q = one - tanh * tanh;
+                [tanh, q, -2.0 * tanh * q, -2.0 * q * (one - 3.0 * tanh * tanh)]
+            }
+        }
+    }
+}
+
+// u^n i pochodne n(n-1)...(n-k+1) u^(n-k). Potęga całkowita przez mnożenie
+// jest dokładniejsza niż exp(n ln u) i poprawna także dla u = 0.
+fn power_derivatives(u: Complex<f64>, n: i32) -> [Complex<f64>; 4] {
+    std::array::from_fn(|k| {
+        let factor: f64 = (0..k as i32).map(|i| (n - i) as f64).product();
+        if factor == 0.0 { Complex::new(0.0, 0.0) } else { u.powi(n - k as i32) * factor }
+    })
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Constant(Complex<f64>),
+    Variable,
+    // Parametr c rodziny funkcji (stały przy różniczkowaniu po z)
+    Parameter,
+    Negate(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Subtract(Box<Expr>, Box<Expr>),
+    Multiply(Box<Expr>, Box<Expr>),
+    Divide(Box<Expr>, Box<Expr>),
+    Power(Box<Expr>, Box<Expr>),
+    Call(Function, Box<Expr>),
+}
+
+impl Expr {
+    fn eval<S: Scalar>(&self, z: S, parameter: Complex<f64>) -> S {
+        match self {
+            Expr::Constant(c) => S::constant(*c),
+            Expr::Variable => z,
+            Expr::Parameter => S::constant(parameter),
+            Expr::Negate(a) => -a.eval(z, parameter),
+            Expr::Add(a, b) => a.eval(z, parameter) + b.eval(z, parameter),
+            Expr::Subtract(a, b) => a.eval(z, parameter) - b.eval(z, parameter),
+            Expr::Multiply(a, b) => a.eval(z, parameter) * b.eval(z, parameter),
+            Expr::Divide(a, b) => a.eval(z, parameter) / b.eval(z, parameter),
+            Expr::Power(base, exponent) => {
+                let base = base.eval(z, parameter);
+                match exponent.integer_constant() {
+                    Some(n) => base.compose(power_derivatives(base.value(), n)),
+                    // Potęga ogólna u^w = exp(w ln u)
+                    None => {
+                        let log = base.compose(Function::Log.derivatives(base.value()));
+                        let product = exponent.eval(z, parameter) * log;
+                        product.compose(Function::Exp.derivatives(product.value()))
+                    }
+                }
+            }
+            Expr::Call(function, a) => {
+                let u = a.eval(z, parameter);
+                u.compose(function.derivatives(u.value()))
+            }
+        }
+    }
+
+    fn contains_variable(&self) -> bool {
+        match self {
+            Expr::Constant(_) | Expr::Parameter => false,
+            Expr::Variable => true,
+            Expr::Negate(a) | Expr::Call(_, a) => a.contains_variable(),
+            Expr::Add(a, b) | Expr::Subtract(a, b) | Expr::Multiply(a, b) | Expr::Divide(a, b) | Expr::Power(a, b) => {
+                a.contains_variable() || b.contains_variable()
+            }
+        }
+    }
+
+    // Wykładnik będący stałą liczbą całkowitą (np. 3 w z^3)
+    fn integer_constant(&self) -> Option<i32> {
+        let value = match self {
+            Expr::Constant(c) => *c,
+            Expr::Negate(a) => match **a {
+                Expr::Constant(c) => -c,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let integer = value.re.round();
+        (value.im == 0.0 && value.re == integer && integer.abs() <= i32::MAX as f64).then_some(integer as i32)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Operator(char),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        let c = chars[pos];
+        if c.is_whitespace() {
+            pos += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = pos;
+            while pos < chars.len() {
+                let c = chars[pos];
+                let exponent = (c == 'e' || c == 'E')
+                    && chars.get(pos + 1).is_some_and(|n| n.is_ascii_digit() || *n == '-' || *n == '+');
+                if c.is_ascii_digit() || c == '.' {
+                    pos += 1;
+                } else if exponent {
+                    pos += 2;
+                } else {
+                    break;
+                }
+            }
+            let number: String = chars[start..pos].iter().collect();
+            let value = number.parse().map_err(|_| format!("Niepoprawna liczba '{}'", number))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() {
+            let start = pos;
+            while pos < chars.len() && chars[pos].is_alphabetic() {
+                pos += 1;
+            }
+            let name: String = chars[start..pos].iter().collect();
+            // "iz" albo "zi" (także "cz" przy parametrze c) to iloczyn, jak
+            // w zapisie wielomianów
+            if Function::parse(&name).is_none() && name.len() > 1 && name.chars().all(|c| "izc".contains(c)) {
+                tokens.extend(name.chars().map(|c| Token::Name(c.to_string())));
+            } else {
+                tokens.push(Token::Name(name));
+            }
+        } else if "+-*/^".contains(c) {
+            tokens.push(Token::Operator(c));
+            pos += 1;
+        } else if c == '(' {
+            tokens.push(Token::Open);
+            pos += 1;
+        } else if c == ')' {
+            tokens.push(Token::Close);
+            pos += 1;
+        } else {
+            return Err(format!("Nieoczekiwany znak '{}'", c));
+        }
+    }
+    Ok(tokens)
+}
+
+// Parser metodą zejść rekurencyjnych. Gramatyka:
+//   suma      = iloczyn (('+' | '-') iloczyn)*
+//   iloczyn   = unarne (('*' | '/') unarne | unarne)*   (mnożenie przez zestawienie: "3z", "2sin(z)")
+//   unarne    = '-' unarne | potęga
+//   potęga    = atom ('^' unarne)?                      (łączna w prawo)
+//   atom      = liczba | 'z' | 'c' | 'i' | 'pi' | 'e' | funkcja '(' suma ')' | '(' suma ')'
+// ('c' tylko w rodzinach z parametrem)
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    parameter: bool,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut left = self.product()?;
+        while let Some(Token::Operator(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            let right = self.product()?;
+            left = if op == '+' {
+                Expr::Add(Box::new(left), Box::new(right))
+            } else {
+                Expr::Subtract(Box::new(left), Box::new(right))
+            };
+        }
+        Ok(left)
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Operator(op @ ('*' | '/'))) => {
+                    let op = *op;
+                    self.pos += 1;
+                    let right = self.unary()?;
+                    left = if op == '*' {
+                        Expr::Multiply(Box::new(left), Box::new(right))
+                    } else {
+                        Expr::Divide(Box::new(left), Box::new(right))
+                    };
+                }
+                // Zestawienie bez operatora oznacza mnożenie
+                Some(Token::Number(_) | Token::Name(_) | Token::Open) => {
+                    let right = self.power()?;
+                    left = Expr::Multiply(Box::new(left), Box::new(right));
+                }
+                _ => return Ok(left),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.peek() == Some(&Token::Operator('-')) {
+            self.pos += 1;
+            return Ok(Expr::Negate(Box::new(self.unary()?)));
+        }
+        if self.peek() == Some(&Token::Operator('+')) {
+            self.pos += 1;
+            return self.unary();
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Expr, String> {
+        let base = self.atom()?;
+        if self.peek() == Some(&Token::Operator('^')) {
+            self.pos += 1;
+            let exponent = self.unary()?;
+            return Ok(Expr::Power(Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expr::Constant(Complex::new(value, 0.0))),
+            Some(Token::Open) => {
+                let inner = self.sum()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err("Brak zamykającego nawiasu".to_string()),
+                }
+            }
+            Some(Token::Name(name)) => match name.as_str() {
+                "z" => Ok(Expr::Variable),
+                "c" if self.parameter => Ok(Expr::Parameter),
+                "i" => Ok(Expr::Constant(Complex::new(0.0, 1.0))),
+                "pi" => Ok(Expr::Constant(Complex::new(std::f64::consts::PI, 0.0))),
+                "e" => Ok(Expr::Constant(Complex::new(std::f64::consts::E, 0.0))),
+                _ => {
+                    let function = Function::parse(&name).ok_or_else(|| format!("Nieznana nazwa '{}'", name))?;
+                    if self.next() != Some(Token::Open) {
+                        return Err(format!("Oczekiwano '(' po {}", name));
+                    }
+                    let argument = self.sum()?;
+                    if self.next() != Some(Token::Close) {
+                        return Err(format!("Brak zamykającego nawiasu po argumencie {}", name));
+                    }
+                    Ok(Expr::Call(function, Box::new(argument)))
+                }
+            },
+            Some(token) => Err(format!("Nieoczekiwany element {:?}", token)),
+            None => Err("Nieoczekiwany koniec wyrażenia".to_string()),
+        }
+    }
+}
+
+// Wartość c dla wyrażeń bez parametru (nie może w nich wystąpić)
+const NO_PARAMETER: Complex<f64> = Complex::new(0.0, 0.0);
+
+// Wyrażenie zespolone jednej zmiennej z, np. "sin(z) - 1" albo "z^3 - 2z + cosh(z)"
+#[derive(Clone, Debug)]
+pub struct Expression {
+    text: String,
+    root: Expr,
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Result<Expression, String> {
+        Expression::parse_with(text, false)
+    }
+
+    // Rodzina funkcji z parametrem c, np. "z^3 + (c-1)z - c"
+    pub fn parse_family(text: &str) -> Result<Expression, String> {
+        Expression::parse_with(text, true)
+    }
+
+    fn parse_with(text: &str, parameter: bool) -> Result<Expression, String> {
+        let mut parser = Parser { tokens: tokenize(text)?, pos: 0, parameter };
+        let root = parser.sum().map_err(|e| format!("{} w: {}", e, text))?;
+        if parser.pos < parser.tokens.len() {
+            return Err(format!("Nieoczekiwany element {:?} w: {}", parser.tokens[parser.pos], text));
+        }
+        Ok(Expression { text: text.trim().to_string(), root })
+    }
+
+    // Stała zespolona zapisana wyrażeniem bez z, np. "0.5+0.3i" albo "exp(i pi/4)"
+    pub fn parse_constant(text: &str) -> Result<Complex<f64>, String> {
+        let expression = Expression::parse(text)?;
+        if expression.root.contains_variable() {
+            return Err(format!("Oczekiwano stałej, a nie funkcji z: {}", text));
+        }
+        Ok(expression.root.eval(Dual::constant(Complex::new(0.0, 0.0)), NO_PARAMETER).value)
+    }
+
+    // Wartość rodziny w punkcie z dla parametru c
+    pub fn eval_family(&self, z: Complex<f64>, c: Complex<f64>) -> Complex<f64> {
+        self.root.eval(Dual::constant(z), c).value
+    }
+
+    // Wartość wyrażenia i jego pochodnej w punkcie
+    pub fn eval_with_derivative(&self, z: Complex<f64>) -> (Complex<f64>, Complex<f64>) {
+        let result = self.root.eval(Dual { value: z, derivative: Complex::new(1.0, 0.0) }, NO_PARAMETER);
+        (result.value, result.derivative)
+    }
+
+    // Funkcja nie-wielomianowa może mieć nieskończenie wiele pierwiastków,
+    // więc szukamy tylko tych w obszarze (powiększonym o połowę z każdej
+    // strony): iteracja Newtona z punktów siatki, potem usunięcie powtórzeń.
+    // Wynik posortowany jak w `Polynomial::roots`.
+    pub fn roots_in(&self, xlims: (f64, f64), ylims: (f64, f64)) -> Vec<Complex<f64>> {
+        const GRID: usize = 48;
+        let margin = (0.5 * (xlims.1 - xlims.0), 0.5 * (ylims.1 - ylims.0));
+        let (x0, x1) = (xlims.0 - margin.0, xlims.1 + margin.0);
+        let (y0, y1) = (ylims.0 - margin.1, ylims.1 + margin.1);
+        let scale = (x1 - x0).max(y1 - y0);
+        let inside = |z: Complex<f64>| z.re >= x0 && z.re <= x1 && z.im >= y0 && z.im <= y1;
+
+        let mut roots: Vec<Complex<f64>> = Vec::new();
+        for a in 0..GRID {
+            for b in 0..GRID {
+                let mut z = Complex::new(
+                    x0 + (x1 - x0) * (a as f64 + 0.5) / GRID as f64,
+                    y0 + (y1 - y0) * (b as f64 + 0.5) / GRID as f64,
+                );
+                let mut converged = false;
+                for _ in 0..200 {
+                    let (f, df) = self.eval_with_derivative(z);
+                    if f.norm() == 0.0 {
+                        converged = true;
+                        break;
+                    }
+                    let step = f / df;
+                    if !step.is_finite() {
+                        break;
+                    }
+                    z -= step;
+                    if step.norm() < 1e-14 * scale {
+                        converged = true;
+                        break;
+                    }
+                }
+                // Pierwiastki wielokrotne są wyznaczone z dokładnością rzędu
+                // pierwiastka z epsilonu maszynowego, stąd luźny próg powtórzeń
+                if converged && z.is_finite() && inside(z) && !roots.iter().any(|r| (r - z).norm() < 1e-6 * scale) {
+                    roots.push(z);
+                }
+            }
+        }
+
+        for root in roots.iter_mut() {
+            if root.re.abs() < 1e-12 * scale {
+                root.re = 0.0;
+            }
+            if root.im.abs() < 1e-12 * scale {
+                root.im = 0.0;
+            }
+        }
+
+        roots.sort_by(|a, b| a.arg().total_cmp(&b.arg()).then(a.norm().total_cmp(&b.norm())));
+        roots
+    }
+}
+
+impl ComplexFunction for Expression {
+    fn value_and_derivative(&self, z: Complex<f64>) -> (Complex<f64>, Complex<f64>) {
+        self.eval_with_derivative(z)
+    }
+
+    fn derivatives(&self, z: Complex<f64>) -> [Complex<f64>; 4] {
+        let [a0, a1, a2, a3] = self.root.eval(Jet::variable(z), NO_PARAMETER).0;
+        [a0, a1, 2.0 * a2, 6.0 * a3]
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn assert_close(actual: Complex<f64>, expected: Complex<f64>) {
+        assert!((actual - expected).norm() < 1e-12 * expected.norm().max(1.0), "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn derivatives_of_product_match_analytic_values() {
+        let expression = Expression::parse("sin(z)*exp(z)").unwrap();
+        let z = Complex::new(0.8, -0.6);
+        let (sin, cos, exp) = (z.sin(), z.cos(), z.exp());
+        let expected = [sin * exp, exp * (sin + cos), 2.0 * exp * cos, 2.0 * exp * (cos - sin)];
+        for (actual, expected) in expression.derivatives(z).into_iter().zip(expected) {
+            assert_close(actual, expected);
+        }
+        let (value, derivative) = expression.eval_with_derivative(z);
+        assert_close(value, expected[0]);
+        assert_close(derivative, expected[1]);
+    }
+
+    #[test]
+    fn derivatives_of_quotient_and_composition() {
+        // f = tan(z) / z^2: f' = (1 + tan^2) / z^2 - 2 tan / z^3
+        let expression = Expression::parse("tan(z)/z^2").unwrap();
+        let z = Complex::new(0.4, 0.3);
+        let tan = z.tan();
+        let expected = (1.0 + tan * tan) / z.powi(2) - 2.0 * tan / z.powi(3);
+        assert_close(expression.derivatives(z)[1], expected);
+
+        // f = sqrt(1 + z^2): f' = z / f, f'' = 1 / f^3
+        let expression = Expression::parse("sqrt(1 + z^2)").unwrap();
+        let f = (1.0 + z * z).sqrt();
+        let [value, first, second, _] = expression.derivatives(z);
+        assert_close(value, f);
+        assert_close(first, z / f);
+        assert_close(second, 1.0 / (f * f * f));
+    }
+
+    #[test]
+    fn parses_constants() {
+        let value = Expression::parse_constant("exp(i pi/4)").unwrap();
+        assert_close(value, Complex::from_polar(1.0, PI / 4.0));
+        assert_close(Expression::parse_constant("0.5+0.3i").unwrap(), Complex::new(0.5, 0.3));
+        assert!(Expression::parse_constant("z + 1").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for text in ["", "sin(z", "z +", "foo(z)", "z)", "2 * * z", "z^"] {
+            assert!(Expression::parse(text).is_err(), "{}", text);
+        }
+        // Parametr c jest dozwolony tylko w rodzinie
+        assert!(Expression::parse("z^3 + c").is_err());
+        assert!(Expression::parse_family("z^3 + c").is_ok());
+    }
+
+    #[test]
+    fn finds_sorted_roots_in_region() {
+        let expression = Expression::parse("sin(z)").unwrap();
+        let roots = expression.roots_in((-4.0, 4.0), (-1.0, 1.0));
+        let expected = [0.0, PI, 2.0 * PI, -PI, -2.0 * PI];
+        assert_eq!(roots.len(), expected.len(), "{:?}", roots);
+        for (root, expected) in roots.into_iter().zip(expected) {
+            assert_close(root, Complex::new(expected, 0.0));
+        }
+    }
+}
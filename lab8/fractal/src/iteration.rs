@@ -0,0 +1,310 @@
+use num_complex::Complex;
+use num_traits::Num;
+use std::fmt;
+use std::ops::Neg;
+use crate::double_double::DoubleDouble;
+
+// Funkcja, której pierwiastków szukamy: wystarczy wartość, wartość
+// z pochodną (metoda Newtona) albo pochodne do trzeciego rzędu
+// (metody wyższych rzędów). Opis tekstowy trafia do statystyk.
+pub trait ComplexFunction: Sync + fmt::Display {
+    fn value_and_derivative(&self, z: Complex<f64>) -> (Complex<f64>, Complex<f64>);
+
+    // [f, f', f'', f''']
+    fn derivatives(&self, z: Complex<f64>) -> [Complex<f64>; 4];
+
+    fn value(&self, z: Complex<f64>) -> Complex<f64> {
+        self.value_and_derivative(z).0
+    }
+
+    // Te same pochodne w arytmetyce double-double (głębokie przybliżenia);
+    // None, gdy funkcja jej nie obsługuje
+    fn derivatives_dd(&self, _z: Complex<DoubleDouble>) -> Option<[Complex<DoubleDouble>; 4]> {
+        None
+    }
+
+    fn value_and_derivative_dd(&self, z: Complex<DoubleDouble>) -> Option<(Complex<DoubleDouble>, Complex<DoubleDouble>)> {
+        self.derivatives_dd(z).map(|d| (d[0], d[1]))
+    }
+}
+
+// Typ współrzędnych, w którym liczona jest iteracja: f64 albo double-double
+// przy przybliżeniach, w których sąsiednie piksele różnią się dopiero na
+// ostatnich bitach f64. Wartości funkcji w double-double są None dla funkcji
+// bez tej arytmetyki.
+pub trait Real: Copy + Default + Num + Neg<Output = Self> + Send + Sync {
+    fn from_f64(x: f64) -> Self;
+    fn from_double_double(x: DoubleDouble) -> Self;
+    fn to_f64(self) -> f64;
+    fn value<F: ComplexFunction + ?Sized>(function: &F, z: Complex<Self>) -> Option<Complex<Self>>;
+    fn value_and_derivative<F: ComplexFunction + ?Sized>(function: &F, z: Complex<Self>) -> Option<(Complex<Self>, Complex<Self>)>;
+    fn derivatives<F: ComplexFunction + ?Sized>(function: &F, z: Complex<Self>) -> Option<[Complex<Self>; 4]>;
+}
+
+impl Real for f64 {
+    fn from_f64(x: f64) -> f64 {
+        x
+    }
+
+    fn from_double_double(x: DoubleDouble) -> f64 {
+        x.to_f64()
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn value<F: ComplexFunction + ?Sized>(function: &F, z: Complex<f64>) -> Option<Complex<f64>> {
+        Some(function.value(z))
+    }
+
+    fn value_and_derivative<F: ComplexFunction + ?Sized>(function: &F, z: Complex<f64>) -> Option<(Complex<f64>, Complex<f64>)> {
+        Some(function.value_and_derivative(z))
+    }
+
+    fn derivatives<F: ComplexFunction + ?Sized>(function: &F, z: Complex<f64>) -> Option<[Complex<f64>; 4]> {
+        Some(function.derivatives(z))
+    }
+}
+
+impl Real for DoubleDouble {
+    fn from_f64(x: f64) -> DoubleDouble {
+        DoubleDouble::new(x)
+    }
+
+    fn from_double_double(x: DoubleDouble) -> DoubleDouble {
+        x
+    }
+
+    fn to_f64(self) -> f64 {
+        DoubleDouble::to_f64(self)
+    }
+
+    fn value<F: ComplexFunction + ?Sized>(function: &F, z: Complex<DoubleDouble>) -> Option<Complex<DoubleDouble>> {
+        function.value_and_derivative_dd(z).map(|(value, _)| value)
+    }
+
+    fn value_and_derivative<F: ComplexFunction + ?Sized>(function: &F, z: Complex<DoubleDouble>) -> Option<(Complex<DoubleDouble>, Complex<DoubleDouble>)> {
+        function.value_and_derivative_dd(z)
+    }
+
+    fn derivatives<F: ComplexFunction + ?Sized>(function: &F, z: Complex<DoubleDouble>) -> Option<[Complex<DoubleDouble>; 4]> {
+        function.derivatives_dd(z)
+    }
+}
+
+// Zaokrąglenie do Complex<f64> (przy f64 bez zmian)
+pub fn to_f64<T: Real>(z: Complex<T>) -> Complex<f64> {
+    Complex::new(z.re.to_f64(), z.im.to_f64())
+}
+
+// k * z; przy f64 daje dokładnie ten sam wynik co `k * z`
+fn scale<T: Real>(k: f64, z: Complex<T>) -> Complex<T> {
+    z * T::from_f64(k)
+}
+
+// Iteracja szukająca pierwiastka
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Iteration {
+    Newton,
+    Halley,
+    // Schröder: Newton dla f/f', zbieżny kwadratowo także do pierwiastków wielokrotnych
+    Schroder,
+    // Householder rzędu 3 (zbieżność czwartego rzędu)
+    Householder,
+    Chebyshev,
+    Secant,
+    Steffensen,
+    // Newton z relaksacją z - a f/f' (rodzina "Nova")
+    Relaxed(Complex<f64>),
+}
+
+// Stan iteracji przenoszony między krokami: poprzedni punkt i wartość
+// funkcji w nim (potrzebne tylko metodzie siecznych)
+#[derive(Default)]
+pub struct IterationState<T: Real = f64> {
+    previous: Option<(Complex<T>, Complex<T>)>,
+}
+
+impl Iteration {
+    pub const NAMES: [&'static str; 8] =
+        ["newton", "halley", "schroder", "householder", "chebyshev", "secant", "steffensen", "relaxed"];
+
+    // `relaxation` to współczynnik a metody z relaksacją
+    pub fn parse(name: &str, relaxation: Complex<f64>) -> Result<Iteration, String> {
+        match name.to_lowercase().as_str() {
+            "newton" => Ok(Iteration::Newton),
+            "halley" => Ok(Iteration::Halley),
+            "schroder" | "schröder" => Ok(Iteration::Schroder),
+            "householder" => Ok(Iteration::Householder),
+            "chebyshev" => Ok(Iteration::Chebyshev),
+            "secant" => Ok(Iteration::Secant),
+            "steffensen" => Ok(Iteration::Steffensen),
+            "relaxed" | "nova" => Ok(Iteration::Relaxed(relaxation)),
+            _ => Err(format!("Nieznana metoda '{}' (dostępne: {})", name, Iteration::NAMES.join(", "))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Iteration::Newton => "newton",
+            Iteration::Halley => "halley",
+            Iteration::Schroder => "schroder",
+            Iteration::Householder => "householder",
+            Iteration::Chebyshev => "chebyshev",
+            Iteration::Secant => "secant",
+            Iteration::Steffensen => "steffensen",
+            Iteration::Relaxed(_) => "relaxed",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Iteration::Relaxed(a) => format!("relaxed (a = {})", a),
+            _ => self.name().to_string(),
+        }
+    }
+
+    // Jeden krok iteracji z punktu z; None, gdy krok nie jest określony
+    // (pochodna równa zeru albo wynik nieskończony) lub funkcja nie
+    // obsługuje arytmetyki typu T
+    pub fn step<T, F>(&self, function: &F, z: Complex<T>, state: &mut IterationState<T>, tol: f64) -> Option<Complex<T>>
+    where
+        T: Real,
+        F: ComplexFunction + ?Sized,
+    {
+        let zero = Complex::new(T::zero(), T::zero());
+        let next = match self {
+            Iteration::Newton | Iteration::Relaxed(_) => {
+                let (fz, dz) = T::value_and_derivative(function, z)?;
+                if to_f64(dz).norm() < tol {
+                    return None;
+                }
+                match self {
+                    Iteration::Relaxed(a) => z - Complex::new(T::from_f64(a.re), T::from_f64(a.im)) * fz / dz,
+                    _ => z - fz / dz,
+                }
+            }
+            Iteration::Halley | Iteration::Schroder | Iteration::Householder | Iteration::Chebyshev => {
+                let [f, d1, d2, d3] = T::derivatives(function, z)?;
+                // Przy pierwiastku wielokrotnym f' dąży do zera, ale krok
+                // Schrödera pozostaje określony
+                if to_f64(d1).norm() < tol && *self != Iteration::Schroder {
+                    return None;
+                }
+                match self {
+                    Iteration::Halley => z - scale(2.0, f) * d1 / (scale(2.0, d1) * d1 - f * d2),
+                    Iteration::Schroder => z - f * d1 / (d1 * d1 - f * d2),
+                    Iteration::Householder => {
+                        z - f * (scale(6.0, d1) * d1 - scale(3.0, f) * d2)
+                            / (scale(6.0, d1) * d1 * d1 - scale(6.0, f) * d1 * d2 + f * f * d3)
+                    }
+                    _ => z - f / d1 * (Complex::new(T::one(), T::zero()) + f * d2 / (scale(2.0, d1) * d1)),
+                }
+            }
+            Iteration::Secant => {
+                let fz = T::value(function, z)?;
+                // Drugi punkt startowy tuż obok pierwszego
+                let (z_prev, f_prev) = match state.previous {
+                    Some(previous) => previous,
+                    None => {
+                        let z_prev = z + T::from_f64(1e-4 * (1.0 + to_f64(z).norm()));
+                        (z_prev, T::value(function, z_prev)?)
+                    }
+                };
+                state.previous = Some((z, fz));
+                if fz == f_prev {
+                    return None;
+                }
+                z - fz * (z - z_prev) / (fz - f_prev)
+            }
+            Iteration::Steffensen => {
+                let fz = T::value(function, z)?;
+                if fz == zero {
+                    return Some(z);
+                }
+                let slope = (T::value(function, z + fz)? - fz) / fz;
+                if to_f64(slope).norm() < tol {
+                    return None;
+                }
+                z - fz / slope
+            }
+        };
+        to_f64(next).is_finite().then_some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polynomial::Polynomial;
+
+    const TOL: f64 = 1e-12;
+
+    fn cube_minus_one() -> Polynomial {
+        Polynomial::parse("z^3 - 1").unwrap()
+    }
+
+    // Kolejne punkty iteracji od z0 (do zatrzymania albo `steps` kroków)
+    fn orbit(method: Iteration, z0: Complex<f64>, steps: usize) -> Vec<Complex<f64>> {
+        let p = cube_minus_one();
+        let mut state = IterationState::default();
+        let mut points = vec![z0];
+        for _ in 0..steps {
+            let z = *points.last().unwrap();
+            match method.step(&p, z, &mut state, TOL) {
+                Some(next) if next != z => points.push(next),
+                _ => break,
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn every_method_converges_to_the_nearest_root() {
+        let methods = Iteration::NAMES.map(|name| Iteration::parse(name, Complex::new(0.8, 0.0)).unwrap());
+        let roots = [0.0, 1.0, -1.0].map(|k: f64| Complex::from_polar(1.0, k * 2.0 * std::f64::consts::PI / 3.0));
+        for method in methods {
+            for root in roots {
+                let z0 = root * Complex::new(1.1, 0.05);
+                let z = *orbit(method, z0, 200).last().unwrap();
+                assert!((z - root).norm() < 1e-10, "{}: z0 = {}, wynik {} zamiast {}", method.label(), z0, z, root);
+            }
+        }
+    }
+
+    // Rząd zbieżności p z trzech kolejnych błędów: e2 / e1 ≈ (e1 / e0)^p
+    fn convergence_order(method: Iteration) -> f64 {
+        let root = Complex::new(1.0, 0.0);
+        let errors: Vec<f64> = orbit(method, Complex::new(1.1, 0.05), 3).iter().map(|z| (z - root).norm()).collect();
+        (errors[2] / errors[1]).ln() / (errors[1] / errors[0]).ln()
+    }
+
+    #[test]
+    fn halley_converges_cubically() {
+        let halley = convergence_order(Iteration::Halley);
+        assert!((2.7..3.3).contains(&halley), "Halley: rząd {}", halley);
+        let newton = convergence_order(Iteration::Newton);
+        assert!((1.8..2.2).contains(&newton), "Newton: rząd {}", newton);
+        let householder = convergence_order(Iteration::Householder);
+        assert!(householder > 3.5, "Householder: rząd {}", householder);
+    }
+
+    #[test]
+    fn zero_derivative_stops_without_nan() {
+        // f'(0) = 0 dla z^3 - 1
+        let p = cube_minus_one();
+        let zero = Complex::new(0.0, 0.0);
+        for method in [Iteration::Newton, Iteration::Relaxed(Complex::new(0.8, 0.0)), Iteration::Halley,
+                       Iteration::Schroder, Iteration::Householder, Iteration::Chebyshev] {
+            let result = method.step(&p, zero, &mut IterationState::default(), TOL);
+            assert!(result.is_none(), "{}: {:?}", method.label(), result);
+        }
+        // Pozostałe metody nie używają f', ale też nie mogą zwrócić NaN
+        for method in [Iteration::Secant, Iteration::Steffensen] {
+            if let Some(z) = method.step(&p, zero, &mut IterationState::default(), TOL) {
+                assert!(z.is_finite(), "{}: {}", method.label(), z);
+            }
+        }
+    }
+}
@@ -1,185 +1,638 @@
-use image::{Rgb, RgbImage};
+use image::RgbImage;
 use num_complex::Complex;
 use rayon::prelude::*;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::time::Instant;
-use std::sync::atomic::{AtomicUsize, Ordering};
-
-fn newton_fractal<F, DF>(
-    f: F,
-    df: DF,
-    xlims: (f64, f64),
-    ylims: (f64, f64),
+use std::env;
+use std::ops::Range;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+mod bench;
+mod double_double;
+mod expression;
+mod iteration;
+mod palette;
+mod parameter;
+mod polynomial;
+mod poster;
+mod sampling;
+mod statistics;
+mod zoom;
+
+use double_double::DoubleDouble;
+use expression::Expression;
+use iteration::{ComplexFunction, Iteration, IterationState, Real};
+use palette::{Coloring, Palette};
+use polynomial::Polynomial;
+use sampling::Sampling;
+use statistics::{BasinMap, BasinStatistics, StatsFormat, NO_ROOT};
+
+// Obszar płaszczyzny zespolonej, rozdzielczość obrazu i parametry iteracji
+#[derive(Clone, Copy)]
+struct FractalConfig {
+    // Środek obszaru w double-double: przy głębokim przybliżeniu szerokość
+    // obszaru jest mniejsza niż dokładność f64 współrzędnych środka
+    center: Complex<DoubleDouble>,
+    // Szerokość i wysokość obszaru
+    span: (f64, f64),
     width: u32,
     height: u32,
     max_iter: u32,
     tol: f64,
-    expected_roots: usize,  // Added parameter for expected number of roots
-    filename: &str,
-)
+    sampling: Sampling,
+}
+
+// Odstęp pikseli (względem wielkości współrzędnych), poniżej którego
+// iterujemy w double-double. Iteracja przy brzegu basenów wzmacnia błędy
+// zaokrągleń, więc przełączamy się na długo przed 2^-52.
+const DOUBLE_DOUBLE_SPACING: f64 = 1e-11;
+// Poniżej tego odstępu nie wystarcza już także double-double
+const DOUBLE_DOUBLE_LIMIT: f64 = 1e-28;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Precision {
+    Double,
+    DoubleDouble,
+}
+
+impl FractalConfig {
+    fn xlims(&self) -> (f64, f64) {
+        let center = self.center.re.to_f64();
+        (center - 0.5 * self.span.0, center + 0.5 * self.span.0)
+    }
+
+    fn ylims(&self) -> (f64, f64) {
+        let center = self.center.im.to_f64();
+        (center - 0.5 * self.span.1, center + 0.5 * self.span.1)
+    }
+
+    // Odstęp sąsiednich pikseli względem wielkości współrzędnych
+    fn relative_spacing(&self) -> f64 {
+        let spacing = (self.span.0 / self.width as f64).min(self.span.1 / self.height as f64);
+        let magnitude = iteration::to_f64(self.center).norm().max(self.span.0).max(self.span.1);
+        spacing / magnitude
+    }
+}
+
+// Arytmetyka iteracji dobrana do przybliżenia: double-double, gdy odstęp
+// pikseli zbliża się do dokładności f64 i funkcja tę arytmetykę obsługuje
+fn iteration_precision<F>(function: &F, config: &FractalConfig) -> Precision
 where
-    F: Fn(Complex<f64>) -> Complex<f64> + Send + Sync,
-    DF: Fn(Complex<f64>) -> Complex<f64> + Send + Sync,
+    F: ComplexFunction + ?Sized,
 {
-    let img = Arc::new(Mutex::new(RgbImage::new(width, height)));
-    let roots_map = Arc::new(Mutex::new(HashMap::<(i32, i32), usize>::new()));
-    let converged_count = Arc::new(AtomicUsize::new(0));
-    
-    // Define a set of vibrant, visually distinct colors
-    let root_colors = vec![
-        Rgb([255, 0, 128]),    // Bright pink
-        Rgb([0, 168, 255]),    // Azure blue
-        Rgb([106, 255, 0]),    // Lime green
-        Rgb([255, 211, 0]),    // Golden yellow
-        Rgb([128, 0, 255]),    // Purple
-        Rgb([255, 106, 0]),    // Orange
-        Rgb([0, 255, 170]),    // Turquoise
-        Rgb([255, 0, 0]),      // Red
-        Rgb([0, 140, 70]),     // Forest green
-        Rgb([200, 80, 255]),   // Violet
-    ];
-    
-    println!("Generowanie fraktala...");
-    let start = Instant::now();
+    if config.relative_spacing() < DOUBLE_DOUBLE_SPACING && function.derivatives_dd(config.center).is_some() {
+        Precision::DoubleDouble
+    } else {
+        Precision::Double
+    }
+}
 
-    // Total number of pixels
-    let total_pixels = (width * height) as usize;
-
-    // Create a vector of pixel coordinates
-    let pixels: Vec<(u32, u32)> = (0..height)
-        .flat_map(|y| (0..width).map(move |x| (y, x)))
-        .collect();
-
-    // Process pixels in parallel
-    pixels.par_iter().for_each(|&(i, j)| {
-        let y = ylims.0 + (ylims.1 - ylims.0) * (i as f64) / (height as f64);
-        let x = xlims.0 + (xlims.1 - xlims.0) * (j as f64) / (width as f64);
-        let mut z = Complex::new(x, y);
-        
-        let mut converged = false;
-        let mut iterations = 0;
-        
-        for iter in 0..max_iter {
-            iterations = iter;
-            let dz = df(z);
-            
-            if dz.norm() < tol {
-                // Derivative too small, might be a problem point
-                break;
-            }
-            
-            let z_new = z - f(z) / dz;
-            
-            if (z_new - z).norm() < tol {
-                // Successfully converged
-                converged = true;
-                z = z_new;
-                break;
-            }
-            
+// Opis arytmetyki z ostrzeżeniem, gdy jej dokładność nie wystarcza
+fn precision_label<F>(function: &F, config: &FractalConfig) -> &'static str
+where
+    F: ComplexFunction + ?Sized,
+{
+    let spacing = config.relative_spacing();
+    match iteration_precision(function, config) {
+        Precision::DoubleDouble if spacing < DOUBLE_DOUBLE_LIMIT => "double-double (za mała dokładność, obraz będzie rozmyty)",
+        Precision::DoubleDouble => "double-double",
+        Precision::Double if spacing < DOUBLE_DOUBLE_SPACING => "f64 (funkcja bez arytmetyki double-double, obraz będzie rozmyty)",
+        Precision::Double => "f64",
+    }
+}
+
+// Liczba wierszy w jednym pasie obrazu przetwarzanym przez wątek
+const STRIP_ROWS: usize = 8;
+
+// Punkt płaszczyzny zespolonej w położeniu (x, y) mierzonym w pikselach od
+// lewego górnego rogu obrazu; (width, height) to prawy dolny róg obszaru
+fn sample_to_complex<T: Real>(config: &FractalConfig, x: f64, y: f64) -> Complex<T> {
+    let x0 = T::from_double_double(config.center.re) - T::from_f64(0.5 * config.span.0);
+    let y0 = T::from_double_double(config.center.im) - T::from_f64(0.5 * config.span.1);
+    Complex::new(
+        x0 + T::from_f64(config.span.0 * x / config.width as f64),
+        y0 + T::from_f64(config.span.1 * y / config.height as f64),
+    )
+}
+
+// Środek piksela (kolumna j, wiersz i)
+fn pixel_to_complex<T: Real>(config: &FractalConfig, j: u32, i: u32) -> Complex<T> {
+    sample_to_complex(config, j as f64 + 0.5, i as f64 + 0.5)
+}
+
+// Wynik iteracji z jednego punktu startowego
+#[derive(Clone, Copy, Debug)]
+struct PointResult {
+    // Indeks pierwiastka, do którego iteracja zbiegła
+    root: Option<usize>,
+    // Liczba wykonanych kroków
+    steps: u32,
+    // Ułamkowa liczba kroków: chwila, w której długość kroku spadła poniżej
+    // tolerancji, interpolowana w skali logarytmicznej między dwoma
+    // ostatnimi krokami - daje ciągłe cieniowanie zamiast pasów
+    smooth: f64,
+}
+
+// Iteracja z punktu startowego wybraną metodą
+fn classify_point<T, F>(function: &F, method: Iteration, roots: &[Complex<f64>], z0: Complex<T>, config: &FractalConfig) -> PointResult
+where
+    T: Real,
+    F: ComplexFunction + ?Sized,
+{
+    let tol = config.tol;
+    let mut z = z0;
+    let mut state = IterationState::default();
+    let mut converged = false;
+    let mut steps = 0;
+    let mut smooth = config.max_iter as f64;
+    let mut previous_step: Option<f64> = None;
+
+    while steps < config.max_iter {
+        steps += 1;
+        // Krok nieokreślony (np. pochodna równa zeru) - punkt problematyczny
+        let Some(z_new) = method.step(function, z, &mut state, config.tol) else {
+            break;
+        };
+
+        let step = iteration::to_f64(z_new - z).norm();
+        if step < tol {
+            // Successfully converged
+            converged = true;
             z = z_new;
+            smooth = match previous_step {
+                Some(prev) => {
+                    let fraction = (prev.ln() - tol.ln()) / (prev.ln() - step.ln());
+                    (steps - 1) as f64 + fraction.clamp(0.0, 1.0)
+                }
+                None => steps as f64,
+            };
+            break;
         }
-        
-        if converged {
-            // Increment the converged counter
-            converged_count.fetch_add(1, Ordering::Relaxed);
+
+        previous_step = Some(step);
+        z = z_new;
+    }
+
+    if !converged {
+        return PointResult { root: None, steps, smooth };
+    }
+
+    // Punkt zbieżny przypisujemy do najbliższego pierwiastka, o ile leży
+    // dostatecznie blisko (dla pierwiastków wielokrotnych Newton zbiega
+    // tylko liniowo, stąd tolerancja większa niż warunek stopu)
+    let z = iteration::to_f64(z);
+    let root = roots.iter()
+        .enumerate()
+        .map(|(idx, r)| (idx, (z - r).norm()))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .filter(|&(_, dist)| dist < tol.sqrt())
+        .map(|(idx, _)| idx);
+    PointResult { root, steps, smooth }
+}
+
+// Iteracja z punktu (x, y) obrazu w wybranej arytmetyce
+fn classify_sample<F>(function: &F, method: Iteration, roots: &[Complex<f64>], config: &FractalConfig, precision: Precision, x: f64, y: f64) -> PointResult
+where
+    F: ComplexFunction + ?Sized,
+{
+    match precision {
+        Precision::Double => {
+            let z0 = sample_to_complex::<f64>(config, x, y);
+            classify_point(function, method, roots, z0, config)
+        }
+        Precision::DoubleDouble => {
+            let z0 = sample_to_complex::<DoubleDouble>(config, x, y);
+            classify_point(function, method, roots, z0, config)
         }
+    }
+}
+
+// Renderuje wiersze `rows` obrazu: kolory (RGB, wiersz po wierszu) i mapę
+// basenów tych wierszy. Wątki rayon wypełniają rozłączne pasy wierszy
+// wspólnych buforów, więc nie potrzeba żadnych blokad. Mapa basenów zawsze
+// pochodzi ze środków pikseli; przy nadpróbkowaniu drugi przebieg zastępuje
+// kolory pikseli średnią z siatki próbek. Wiersz nad i pod zakresem liczony
+// jest dodatkowo, żeby przy próbkowaniu adaptacyjnym brzegi basenów na
+// granicy zakresu wyglądały tak samo jak przy renderowaniu całości.
+fn render_rows<F>(function: &F, method: Iteration, roots: &[Complex<f64>], config: &FractalConfig, coloring: &Coloring, rows: Range<usize>) -> (Vec<u8>, BasinMap)
+where
+    F: ComplexFunction + ?Sized,
+{
+    let width = config.width as usize;
+    let precision = iteration_precision(function, config);
+    let top = rows.start.saturating_sub(1);
+    let bottom = (rows.end + 1).min(config.height as usize);
+    let mut buffer = vec![0u8; width * 3 * (bottom - top)];
+    let mut map = BasinMap::new(width, bottom - top);
+
+    buffer
+        .par_chunks_mut(width * 3 * STRIP_ROWS)
+        .zip(map.labels.par_chunks_mut(width * STRIP_ROWS))
+        .zip(map.steps.par_chunks_mut(width * STRIP_ROWS))
+        .enumerate()
+        .for_each(|(strip, ((strip_rows, labels), steps))| {
+            for (offset, row) in strip_rows.chunks_mut(width * 3).enumerate() {
+                let i = top + strip * STRIP_ROWS + offset;
+                for (j, pixel) in row.chunks_mut(3).enumerate() {
+                    let result = classify_sample(function, method, roots, config, precision, j as f64 + 0.5, i as f64 + 0.5);
+                    let color = coloring.color(result.root, result.smooth, roots, config.max_iter);
+                    pixel.copy_from_slice(&color.0);
+                    labels[offset * width + j] = result.root.map_or(NO_ROOT, |idx| idx as u16);
+                    steps[offset * width + j] = result.steps;
+                }
+            }
+        });
+
+    let sampling = config.sampling;
+    if sampling.samples > 1 {
+        let map = &map;
+        buffer
+            .par_chunks_mut(width * 3 * STRIP_ROWS)
+            .enumerate()
+            .for_each(|(strip, strip_rows)| {
+                for (offset, row) in strip_rows.chunks_mut(width * 3).enumerate() {
+                    let local = strip * STRIP_ROWS + offset;
+                    let i = top + local;
+                    if !rows.contains(&i) {
+                        continue;
+                    }
+                    for (j, pixel) in row.chunks_mut(3).enumerate() {
+                        if sampling.adaptive && !map.is_edge(local, j) {
+                            continue;
+                        }
+                        let mut sum = [0u32; 3];
+                        for (dx, dy) in sampling.offsets(i as u32, j as u32) {
+                            let result = classify_sample(function, method, roots, config, precision, j as f64 + dx, i as f64 + dy);
+                            let color = coloring.color(result.root, result.smooth, roots, config.max_iter);
+                            for (total, channel) in sum.iter_mut().zip(color.0) {
+                                *total += channel as u32;
+                            }
+                        }
+                        let count = sampling.samples * sampling.samples;
+                        pixel.copy_from_slice(&sum.map(|total| ((total + count / 2) / count) as u8));
+                    }
+                }
+            });
+    }
+
+    // Bez wierszy dodatkowych
+    let skip = rows.start - top;
+    buffer.drain(..width * 3 * skip);
+    buffer.truncate(width * 3 * rows.len());
+    (buffer, map.rows(skip..skip + rows.len()))
+}
+
+// Renderuje cały fraktal
+fn render_fractal<F>(function: &F, method: Iteration, roots: &[Complex<f64>], config: &FractalConfig, coloring: &Coloring) -> (RgbImage, BasinMap)
+where
+    F: ComplexFunction + ?Sized,
+{
+    let (buffer, map) = render_rows(function, method, roots, config, coloring, 0..config.height as usize);
+    let img = RgbImage::from_raw(config.width, config.height, buffer).unwrap();
+    (img, map)
+}
 
-        // Round the root to a fixed precision to avoid floating point issues
-        let rounded_real = (z.re * 1e6).round() as i32;
-        let rounded_imag = (z.im * 1e6).round() as i32;
-        let key = (rounded_real, rounded_imag);
-        
-        // Determine color for this pixel
-        let color = {
-            let mut roots = roots_map.lock().unwrap();
-            
-            if let Some(&idx) = roots.get(&key) {
-                root_colors[idx % root_colors.len()]
-            } else {
-                let idx = roots.len();
-                roots.insert(key, idx);
-                root_colors[idx % root_colors.len()]
+// Pliki wynikowe: obraz i opcjonalnie statystyki basenów (.json albo .csv)
+struct OutputFiles {
+    image: String,
+    statistics: Option<String>,
+}
+
+impl OutputFiles {
+    // Przyrostek (np. nazwa metody) przed rozszerzeniem każdego pliku
+    fn with_suffix(&self, suffix: &str) -> OutputFiles {
+        let add = |filename: &str| {
+            let path = Path::new(filename);
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("newton_fractal");
+            match path.extension().and_then(|s| s.to_str()) {
+                Some(extension) => path.with_file_name(format!("{}_{}.{}", stem, suffix, extension)),
+                None => path.with_file_name(format!("{}_{}", stem, suffix)),
             }
+            .to_string_lossy()
+            .into_owned()
         };
-        
-        // Update pixel in the shared image
-        img.lock().unwrap().put_pixel(j, i, color);
-    });
+        OutputFiles { image: add(&self.image), statistics: self.statistics.as_deref().map(add) }
+    }
+}
+
+// `roots` to pierwiastki wyznaczone z góry (wielomian - metodą Abertha,
+// wyrażenie - w oglądanym obszarze)
+fn newton_fractal<F>(function: &F, method: Iteration, roots: &[Complex<f64>], config: &FractalConfig, coloring: &Coloring, output: &OutputFiles) -> Result<(Duration, BasinStatistics), Box<dyn std::error::Error>>
+where
+    F: ComplexFunction + ?Sized,
+{
+    println!("Metoda: {}", method.label());
+    println!("Arytmetyka: {}", precision_label(function, config));
+    if config.sampling.samples > 1 {
+        println!("Próbkowanie: {}", config.sampling.label());
+    }
+    println!("Generowanie fraktala...");
+    let start = Instant::now();
+
+    let (img, map) = render_fractal(function, method, roots, config, coloring);
 
     let duration = start.elapsed();
     println!("Fraktal wygenerowany w czasie: {:?}", duration);
-    
-    // Calculate root identification effectiveness
-    let root_count = roots_map.lock().unwrap().len();
-    
+
     // Calculate pixel convergence effectiveness
-    let converged = converged_count.load(Ordering::Relaxed);
+    let total_pixels = (config.width * config.height) as usize;
+    let converged = map.converged();
     let pixel_effectiveness = (converged as f64 / total_pixels as f64) * 100.0;
-    println!("Skuteczność zbieżności: {:.2}% (zbiegło {} z {} pikseli)", 
+    println!("Skuteczność zbieżności: {:.2}% (zbiegło {} z {} pikseli)",
              pixel_effectiveness, converged, total_pixels);
-    
+
+    let statistics = BasinStatistics::compute(&map, roots, config, &function.to_string(), &method.label());
+    statistics.print();
+
     // Save the final image
-    let final_img = Arc::try_unwrap(img).unwrap().into_inner().unwrap();
-    final_img.save(filename).unwrap();
-}
-
-fn main() {
-    // For z⁴ - 1 (four roots)
-    let f1 = |z: Complex<f64>| z.powu(4) - Complex::new(1.0, 0.0);
-    let df1 = |z: Complex<f64>| Complex::new(4.0, 0.0) * z.powu(3);
-
-    newton_fractal(
-        f1,
-        df1,
-        (-2.0, 2.0),
-        (-2.0, 2.0),
-        3000,  // width
-        3000,  // height
-        30,    // max_iter
-        1e-6,  // tolerance
-        4,     // expected roots
-        "newton_fractal_4.png",
-    );
-
-    println!("Fraktal zapisany jako newton_fractal_4.png");
-
-    // For z³ - 1 (three roots)
-    let f2 = |z: Complex<f64>| z.powu(3) - Complex::new(1.0, 0.0);
-    let df2 = |z: Complex<f64>| Complex::new(3.0, 0.0) * z.powu(2);
-
-    newton_fractal(
-        f2,
-        df2,
-        (-2.0, 2.0),
-        (-2.0, 2.0),
-        3000,  // width
-        3000,  // height
-        30,    // max_iter
-        1e-6,  // tolerance
-        3,     // expected roots
-        "newton_fractal_3.png",
-    );
-
-    println!("Fraktal zapisany jako newton_fractal_3.png");
-
-    // For z⁵ - 1 (five roots)
-    let f3 = |z: Complex<f64>| z.powu(5) - Complex::new(1.0, 0.0);
-    let df3 = |z: Complex<f64>| Complex::new(5.0, 0.0) * z.powu(4);
-
-    newton_fractal(
-        f3,
-        df3,
-        (-2.0, 2.0),
-        (-2.0, 2.0),
-        3000,  // width
-        3000,  // height
-        30,    // max_iter
-        1e-6,  // tolerance
-        5,     // expected roots
-        "newton_fractal_5.png",
-    );
-
-    println!("Fraktal zapisany jako newton_fractal_5.png");
-}
\ No newline at end of file
+    img.save(&output.image)?;
+    println!("Fraktal zapisany jako {}", output.image);
+    if let Some(filename) = &output.statistics {
+        statistics.write(filename)?;
+        println!("Statystyki zapisane jako {}", filename);
+    }
+    Ok((duration, statistics))
+}
+
+// Renderuje fraktal każdą z metod; przy kilku metodach nazwy plików dostają
+// przyrostek z nazwą metody, a na końcu drukowane jest porównanie
+fn render_methods<F>(function: &F, roots: &[Complex<f64>], methods: &[Iteration], config: &FractalConfig, coloring: &Coloring, output: &OutputFiles) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: ComplexFunction + ?Sized,
+{
+    println!("Pierwiastki:");
+    for (idx, root) in roots.iter().enumerate() {
+        println!("  {}: {:.6} {:+.6}i", idx, root.re, root.im);
+    }
+
+    if let [method] = methods {
+        newton_fractal(function, *method, roots, config, coloring, output)?;
+        return Ok(());
+    }
+
+    let mut results = Vec::new();
+    for &method in methods {
+        let (duration, statistics) = newton_fractal(function, method, roots, config, coloring, &output.with_suffix(method.name()))?;
+        results.push((method, duration, statistics));
+    }
+
+    println!("{:<28} {:>12} {:>12} {:>16} {:>10}", "metoda", "czas", "zbieżność", "śr. iteracji", "wymiar");
+    for (method, duration, statistics) in results {
+        println!("{:<28} {:>12.3?} {:>11.2}% {:>16.2} {:>10.4}",
+                 method.label(), duration, 100.0 * (1.0 - statistics.non_converged_fraction),
+                 statistics.mean_iterations(), statistics.boundary_dimension);
+    }
+    Ok(())
+}
+
+// Wartość opcji podanej jako `--nazwa wartość`
+fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .map(|s| s.as_str())
+}
+
+// Punkt płaszczyzny: zapis dziesiętny w pełnej precyzji double-double,
+// poza tym dowolna stała (np. "exp(i pi/4)")
+fn parse_center(value: &str) -> Result<Complex<DoubleDouble>, String> {
+    if let Ok(center) = double_double::parse_complex(value) {
+        return Ok(center);
+    }
+    let center = Expression::parse_constant(value)?;
+    Ok(Complex::new(DoubleDouble::new(center.re), DoubleDouble::new(center.im)))
+}
+
+fn has_flag(args: &[String], name: &str) -> bool {
+    args.iter().any(|a| a == name)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = env::args().collect();
+    let config = FractalConfig {
+        center: Complex::new(DoubleDouble::new(0.0), DoubleDouble::new(0.0)),
+        span: (4.0, 4.0),
+        width: 3000,
+        height: 3000,
+        max_iter: 30,
+        tol: 1e-6,
+        sampling: Sampling::default(),
+    };
+
+    // Koszt blokad w schemacie poprzedniej wersji renderera: `fractal bench`
+    if args.get(1).map(String::as_str) == Some("bench") {
+        let polynomial = Polynomial::parse("z^5 - 1")?;
+        bench::run_benchmark(&polynomial, &polynomial.roots()?, &config, &[500, 1000, 2000, 3000])?;
+        return Ok(());
+    }
+
+    // Antyaliasing: --samples N (siatka N x N próbek na piksel), --jitter
+    // (próbki w losowych miejscach komórek siatki), --adaptive (siatka tylko
+    // na brzegach basenów); same --jitter/--adaptive oznaczają siatkę 4 x 4
+    let jitter = has_flag(&args, "--jitter");
+    let adaptive = has_flag(&args, "--adaptive");
+    let samples = match option_value(&args, "--samples") {
+        Some(value) => value.parse::<u32>().ok().filter(|v| *v > 0)
+            .ok_or_else(|| format!("Niepoprawna liczba próbek: {}", value))?,
+        None if jitter || adaptive => 4,
+        None => 1,
+    };
+    let config = FractalConfig { sampling: Sampling { samples, jitter, adaptive }, ..config };
+
+    // Plik z obrazem: --output plik.png; statystyki basenów: --stats plik.json|plik.csv
+    let output = OutputFiles {
+        image: option_value(&args, "--output").unwrap_or("newton_fractal.png").to_string(),
+        statistics: option_value(&args, "--stats").map(str::to_string),
+    };
+    if let Some(filename) = &output.statistics {
+        StatsFormat::from_filename(filename)?;
+    }
+
+    // Metoda iteracji: --method newton|halley|schroder|householder|chebyshev|
+    // secant|steffensen|relaxed albo `all` (wszystkie, z porównaniem);
+    // współczynnik metody z relaksacją: --relaxation "0.5+0.3i"
+    let relaxation = match option_value(&args, "--relaxation") {
+        Some(text) => Expression::parse_constant(text)?,
+        None => Complex::new(0.5, 0.0),
+    };
+    let methods = match option_value(&args, "--method") {
+        Some("all") => Iteration::NAMES
+            .iter()
+            .map(|name| Iteration::parse(name, relaxation))
+            .collect::<Result<Vec<_>, _>>()?,
+        Some(name) => vec![Iteration::parse(name, relaxation)?],
+        None => vec![Iteration::Newton],
+    };
+
+    // Kolorowanie: --palette classic|viridis|hsv|plik_z_gradientem,
+    // --shading (przyciemnianie wg liczby iteracji), --heatmap (sama liczba
+    // iteracji; domyślnie w palecie viridis)
+    let heatmap = has_flag(&args, "--heatmap");
+    let palette = match option_value(&args, "--palette") {
+        Some(name) => Palette::parse(name)?,
+        None if heatmap => Palette::Viridis,
+        None => Palette::Classic,
+    };
+    let coloring = Coloring { palette, shading: has_flag(&args, "--shading"), heatmap };
+
+    // Animacja przybliżenia: `fractal zoom [funkcja] --center c --width w
+    // --frames n --size px --delay ms --output zoom.gif|katalog`
+    if args.get(1).map(String::as_str) == Some("zoom") {
+        let mut options = zoom::ZoomOptions::default();
+        if let Some(value) = option_value(&args, "--center") {
+            options.center = parse_center(value)?;
+        }
+        if let Some(value) = option_value(&args, "--width") {
+            options.width = value.parse::<f64>().ok().filter(|v| *v > 0.0)
+                .ok_or_else(|| format!("Niepoprawna szerokość: {}", value))?;
+        }
+        if let Some(value) = option_value(&args, "--frames") {
+            options.frames = value.parse::<usize>().ok().filter(|v| *v > 0)
+                .ok_or_else(|| format!("Niepoprawna liczba klatek: {}", value))?;
+        }
+        if let Some(value) = option_value(&args, "--size") {
+            options.size = value.parse::<u32>().ok().filter(|v| *v > 0)
+                .ok_or_else(|| format!("Niepoprawny rozmiar klatki: {}", value))?;
+        }
+        if let Some(value) = option_value(&args, "--delay") {
+            options.frame_delay_ms = value.parse::<u32>()
+                .map_err(|_| format!("Niepoprawny czas klatki: {}", value))?;
+        }
+        let [method] = methods[..] else {
+            return Err("Animacja przybliżenia obsługuje jedną metodę naraz".into());
+        };
+        let output = option_value(&args, "--output").unwrap_or("newton_zoom.gif");
+        let text = args.get(2).filter(|t| !t.starts_with("--")).map_or("z^3 - 1", String::as_str);
+        return match Polynomial::parse(text) {
+            Ok(polynomial) => {
+                println!("Wielomian: {}", polynomial);
+                zoom::render_zoom(&polynomial, method, &polynomial.roots()?, &config, &coloring, &options, output)
+            }
+            Err(_) => {
+                let expression = Expression::parse(text)?;
+                println!("Funkcja: {}", expression);
+                let roots = expression.roots_in(config.xlims(), config.ylims());
+                zoom::render_zoom(&expression, method, &roots, &config, &coloring, &options, output)
+            }
+        };
+    }
+
+    // Plakat renderowany pasami prosto do pliku: `fractal poster [funkcja]
+    // --size 20000x20000 --output plakat.png --tiles plakat` (--tiles:
+    // dodatkowo piramida kafelków Deep Zoom plakat.dzi + plakat_files/)
+    if args.get(1).map(String::as_str) == Some("poster") {
+        if output.statistics.is_some() {
+            return Err("Statystyki basenów wymagają całej mapy w pamięci; plakat ich nie obsługuje".into());
+        }
+        let (width, height) = match option_value(&args, "--size") {
+            Some(value) => {
+                let invalid = || format!("Niepoprawny rozmiar plakatu (np. 20000x10000): {}", value);
+                let (w, h) = value.split_once('x').unwrap_or((value, value));
+                let w = w.parse::<u32>().ok().filter(|v| *v > 0).ok_or_else(invalid)?;
+                let h = h.parse::<u32>().ok().filter(|v| *v > 0).ok_or_else(invalid)?;
+                (w, h)
+            }
+            None => (10000, 10000),
+        };
+        // Szerokość obszaru bez zmian, wysokość wg proporcji plakatu
+        let config = FractalConfig {
+            span: (config.span.0, config.span.0 * height as f64 / width as f64),
+            width,
+            height,
+            ..config
+        };
+        let [method] = methods[..] else {
+            return Err("Plakat obsługuje jedną metodę naraz".into());
+        };
+        let output = option_value(&args, "--output").unwrap_or("newton_poster.png");
+        let tiles = option_value(&args, "--tiles");
+        let text = args.get(2).filter(|t| !t.starts_with("--")).map_or("z^3 - 1", String::as_str);
+        return match Polynomial::parse(text) {
+            Ok(polynomial) => {
+                println!("Wielomian: {}", polynomial);
+                poster::render_poster(&polynomial, method, &polynomial.roots()?, &config, &coloring, output, tiles)
+            }
+            Err(_) => {
+                let expression = Expression::parse(text)?;
+                println!("Funkcja: {}", expression);
+                let roots = expression.roots_in(config.xlims(), config.ylims());
+                poster::render_poster(&expression, method, &roots, &config, &coloring, output, tiles)
+            }
+        };
+    }
+
+    // Płaszczyzna parametru rodziny wielomianów: `fractal parameter
+    // "z^3 + (c-1)z - c" --center c --width w --size px --output plik.png`;
+    // piksel to parametr c, a iteracja startuje z punktów krytycznych
+    if args.get(1).map(String::as_str) == Some("parameter") {
+        if output.statistics.is_some() {
+            return Err("Statystyki basenów nie dotyczą płaszczyzny parametru".into());
+        }
+        let center = match option_value(&args, "--center") {
+            Some(value) => parse_center(value)?,
+            None => config.center,
+        };
+        let width = match option_value(&args, "--width") {
+            Some(value) => value.parse::<f64>().ok().filter(|v| *v > 0.0)
+                .ok_or_else(|| format!("Niepoprawna szerokość: {}", value))?,
+            None => config.span.0,
+        };
+        let size = match option_value(&args, "--size") {
+            Some(value) => value.parse::<u32>().ok().filter(|v| *v > 0)
+                .ok_or_else(|| format!("Niepoprawny rozmiar obrazu: {}", value))?,
+            None => 1000,
+        };
+        // Więcej iteracji niż przy zwykłym fraktalu: czarne mają zostać tylko
+        // parametry z przyciągającym cyklem, a nie te wolno zbieżne
+        let config = FractalConfig { center, span: (width, width), width: size, height: size, max_iter: 200, ..config };
+        let [method] = methods[..] else {
+            return Err("Płaszczyzna parametru obsługuje jedną metodę naraz".into());
+        };
+        let text = args.get(2).filter(|t| !t.starts_with("--")).map_or("z^3 + (c-1)z - c", String::as_str);
+        let family = parameter::ParameterFamily::parse(text)?;
+        let output = option_value(&args, "--output").unwrap_or("newton_parameter.png");
+        return parameter::render_parameter_plane(&family, method, &config, &coloring, output);
+    }
+
+    // Dowolna funkcja zespolona (`fractal --expr "sin(z) - 1"`); pochodne
+    // liczone automatycznie, pierwiastki szukane w oglądanym obszarze
+    if let Some(text) = option_value(&args, "--expr") {
+        return render_expression(&Expression::parse(text)?, &methods, &config, &coloring, &output);
+    }
+
+    // Wielomian jako tekst (`fractal "z^5 - 3z^2 + 1+2i"`) albo lista
+    // współczynników od najwyższej potęgi (`fractal --coeffs "1, 0, 2+i, -1"`);
+    // tekst, który nie jest wielomianem, traktujemy jak wyrażenie
+    let polynomial = match (option_value(&args, "--coeffs"), args.get(1)) {
+        (Some(list), _) => Some(Polynomial::from_coefficient_list(list)?),
+        (None, Some(text)) if !text.starts_with("--") => match Polynomial::parse(text) {
+            Ok(polynomial) => Some(polynomial),
+            Err(_) => {
+                return render_expression(&Expression::parse(text)?, &methods, &config, &coloring, &output);
+            }
+        },
+        _ => None,
+    };
+    if let Some(polynomial) = polynomial {
+        return render_polynomial(&polynomial, &methods, &config, &coloring, &output);
+    }
+
+    // Bez argumentów: z^n - 1 dla n = 4, 3, 5
+    for n in [4, 3, 5] {
+        let output = OutputFiles {
+            image: format!("newton_fractal_{}.png", n),
+            statistics: output.statistics.as_ref().map(|_| format!("newton_fractal_{}.json", n)),
+        };
+        render_polynomial(&Polynomial::parse(&format!("z^{} - 1", n))?, &methods, &config, &coloring, &output)?;
+    }
+    Ok(())
+}
+
+fn render_polynomial(polynomial: &Polynomial, methods: &[Iteration], config: &FractalConfig, coloring: &Coloring, output: &OutputFiles) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Wielomian: {}", polynomial);
+    render_methods(polynomial, &polynomial.roots()?, methods, config, coloring, output)
+}
+
+fn render_expression(expression: &Expression, methods: &[Iteration], config: &FractalConfig, coloring: &Coloring, output: &OutputFiles) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Funkcja: {}", expression);
+    render_methods(expression, &expression.roots_in(config.xlims(), config.ylims()), methods, config, coloring, output)
+}
@@ -0,0 +1,173 @@
+use image::Rgb;
+use num_complex::Complex;
+use std::f64::consts::PI;
+
+// Kolor punktów, które nie zbiegły do żadnego pierwiastka
+pub const NON_CONVERGED_COLOR: Rgb<u8> = Rgb([0, 0, 0]);
+
+// Define a set of vibrant, visually distinct colors
+const ROOT_COLORS: [Rgb<u8>; 10] = [
+    Rgb([255, 0, 128]),    // Bright pink
+    Rgb([0, 168, 255]),    // Azure blue
+    Rgb([106, 255, 0]),    // Lime green
+    Rgb([255, 211, 0]),    // Golden yellow
+    Rgb([128, 0, 255]),    // Purple
+    Rgb([255, 106, 0]),    // Orange
+    Rgb([0, 255, 170]),    // Turquoise
+    Rgb([255, 0, 0]),      // Red
+    Rgb([0, 140, 70]),     // Forest green
+    Rgb([200, 80, 255]),   // Violet
+];
+
+// Punkty kontrolne mapy viridis (matplotlib) co 1/8
+const VIRIDIS: [Rgb<u8>; 9] = [
+    Rgb([68, 1, 84]),
+    Rgb([72, 40, 120]),
+    Rgb([62, 73, 137]),
+    Rgb([49, 104, 142]),
+    Rgb([38, 130, 142]),
+    Rgb([31, 158, 137]),
+    Rgb([53, 183, 121]),
+    Rgb([110, 206, 88]),
+    Rgb([253, 231, 37]),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Palette {
+    // Stała tabela kolorów pierwiastków
+    Classic,
+    Viridis,
+    // Barwa wg argumentu pierwiastka
+    Hsv,
+    // Gradient wczytany z pliku
+    Gradient(Vec<Rgb<u8>>),
+}
+
+impl Palette {
+    // Nazwa wbudowanej palety albo ścieżka do pliku z gradientem: jeden kolor
+    // w wierszu, "#rrggbb" albo "r g b" (puste wiersze są pomijane)
+    pub fn parse(name: &str) -> Result<Palette, String> {
+        match name {
+            "classic" => Ok(Palette::Classic),
+            "viridis" => Ok(Palette::Viridis),
+            "hsv" => Ok(Palette::Hsv),
+            path => {
+                let text = std::fs::read_to_string(path)
+                    .map_err(|e| format!("Nie można wczytać palety '{}': {}", path, e))?;
+                let colors = text
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(parse_color)
+                    .collect::<Result<Vec<_>, String>>()
+                    .map_err(|e| format!("{} w pliku {}", e, path))?;
+                if colors.len() < 2 {
+                    return Err(format!("Gradient w pliku {} musi mieć co najmniej dwa kolory", path));
+                }
+                Ok(Palette::Gradient(colors))
+            }
+        }
+    }
+
+    // Kolor dla t z przedziału [0, 1]
+    pub fn sample(&self, t: f64) -> Rgb<u8> {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Palette::Classic => interpolate(&ROOT_COLORS, t),
+            Palette::Viridis => interpolate(&VIRIDIS, t),
+            Palette::Hsv => hsv(t),
+            Palette::Gradient(colors) => interpolate(colors, t),
+        }
+    }
+
+    // Kolor basenu pierwiastka o numerze `idx` spośród `roots`
+    pub fn root_color(&self, idx: usize, roots: &[Complex<f64>]) -> Rgb<u8> {
+        match self {
+            Palette::Classic => ROOT_COLORS[idx % ROOT_COLORS.len()],
+            Palette::Hsv => hsv((roots[idx].arg() / (2.0 * PI)).rem_euclid(1.0)),
+            _ => self.sample((idx as f64 + 0.5) / roots.len() as f64),
+        }
+    }
+}
+
+fn parse_color(line: &str) -> Result<Rgb<u8>, String> {
+    let invalid = || format!("Niepoprawny kolor '{}'", line);
+    if let Some(hex) = line.strip_prefix('#') {
+        // from_str_radix przyjmuje też znak ("+12345"), więc najpierw
+        // sprawdzamy, że to dokładnie sześć cyfr szesnastkowych
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let value = u32::from_str_radix(hex, 16).map_err(|_| invalid())?;
+        return Ok(Rgb([(value >> 16) as u8, (value >> 8) as u8, value as u8]));
+    }
+    let channels = line
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<u8>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, String>>()?;
+    match channels[..] {
+        [r, g, b] => Ok(Rgb([r, g, b])),
+        _ => Err(invalid()),
+    }
+}
+
+// Liniowa interpolacja między równo rozłożonymi kolorami
+fn interpolate(colors: &[Rgb<u8>], t: f64) -> Rgb<u8> {
+    let position = t * (colors.len() - 1) as f64;
+    let k = (position.floor() as usize).min(colors.len() - 2);
+    let f = position - k as f64;
+    let (a, b) = (colors[k].0, colors[k + 1].0);
+    Rgb(std::array::from_fn(|c| (a[c] as f64 + f * (b[c] as f64 - a[c] as f64)).round() as u8))
+}
+
+// Pełne nasycenie i jasność, barwa h z [0, 1]
+fn hsv(h: f64) -> Rgb<u8> {
+    let h6 = h * 6.0;
+    let x = 1.0 - (h6 % 2.0 - 1.0).abs();
+    let (r, g, b) = match h6 as u32 % 6 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    Rgb([(255.0 * r).round() as u8, (255.0 * g).round() as u8, (255.0 * b).round() as u8])
+}
+
+// Sposób kolorowania pikseli
+#[derive(Clone, Debug)]
+pub struct Coloring {
+    pub palette: Palette,
+    // Przyciemnianie wg (ułamkowej) liczby iteracji do zbieżności
+    pub shading: bool,
+    // Sama liczba iteracji jako mapa ciepła, bez podziału na baseny
+    pub heatmap: bool,
+}
+
+impl Default for Coloring {
+    fn default() -> Self {
+        Coloring { palette: Palette::Classic, shading: false, heatmap: false }
+    }
+}
+
+impl Coloring {
+    // `smooth` to ułamkowa liczba iteracji; skala logarytmiczna, bo
+    // zdecydowana większość punktów zbiega w kilku krokach
+    pub fn color(&self, root: Option<usize>, smooth: f64, roots: &[Complex<f64>], max_iter: u32) -> Rgb<u8> {
+        let Some(idx) = root else {
+            return NON_CONVERGED_COLOR;
+        };
+        let level = (1.0 + smooth).ln() / (1.0 + max_iter as f64).ln();
+        if self.heatmap {
+            return self.palette.sample(level);
+        }
+        let color = self.palette.root_color(idx, roots);
+        if !self.shading {
+            return color;
+        }
+        let brightness = 1.0 - level.clamp(0.0, 1.0);
+        Rgb(color.0.map(|c| (c as f64 * brightness).round() as u8))
+    }
+}
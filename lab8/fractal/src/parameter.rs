@@ -0,0 +1,162 @@
+use image::{Rgb, RgbImage};
+use num_complex::Complex;
+use rayon::prelude::*;
+use std::f64::consts::PI;
+use std::fmt;
+use std::time::Instant;
+use crate::expression::Expression;
+use crate::iteration::Iteration;
+use crate::palette::Coloring;
+use crate::polynomial::Polynomial;
+use crate::{classify_point, pixel_to_complex, FractalConfig, STRIP_ROWS};
+
+// Liczba próbek na okręgu jednostkowym w każdej zmiennej; rodzina musi
+// mieć w z i w c stopień mniejszy niż tyle
+const SAMPLES: usize = 16;
+
+// Jednoparametrowa rodzina wielomianów p_c(z), np. z^3 + (c-1)z - c
+pub struct ParameterFamily {
+    expression: Expression,
+    // coefficients[k][m]: współczynnik przy z^k c^m
+    coefficients: Vec<Vec<Complex<f64>>>,
+}
+
+impl ParameterFamily {
+    // Współczynniki wyznaczane raz, dwuwymiarową dyskretną transformatą
+    // Fouriera wartości na okręgach |z| = |c| = 1
+    pub fn parse(text: &str) -> Result<ParameterFamily, String> {
+        let expression = Expression::parse_family(text)?;
+        let root_of_unity = |k: usize| Complex::from_polar(1.0, 2.0 * PI * (k % SAMPLES) as f64 / SAMPLES as f64);
+        let values: Vec<Vec<Complex<f64>>> = (0..SAMPLES)
+            .map(|j| (0..SAMPLES).map(|l| expression.eval_family(root_of_unity(j), root_of_unity(l))).collect())
+            .collect();
+
+        let mut coefficients = vec![vec![Complex::new(0.0, 0.0); SAMPLES]; SAMPLES];
+        for (k, row) in coefficients.iter_mut().enumerate() {
+            for (m, coefficient) in row.iter_mut().enumerate() {
+                let mut sum = Complex::new(0.0, 0.0);
+                for (j, samples) in values.iter().enumerate() {
+                    for (l, value) in samples.iter().enumerate() {
+                        sum += value * root_of_unity(SAMPLES - (j * k + l * m) % SAMPLES);
+                    }
+                }
+                *coefficient = sum / (SAMPLES * SAMPLES) as f64;
+            }
+        }
+
+        // Szum zaokrągleń zerujemy względem największego współczynnika
+        let scale = coefficients.iter().flatten().map(|c| c.norm()).fold(0.0, f64::max);
+        if !scale.is_finite() {
+            return Err(format!("Rodzina nie jest określona na okręgu jednostkowym: {}", text));
+        }
+        for coefficient in coefficients.iter_mut().flatten() {
+            if coefficient.norm() < 1e-12 * scale {
+                *coefficient = Complex::new(0.0, 0.0);
+            }
+        }
+        let degree = coefficients.iter().rposition(|row| row.iter().any(|c| c.norm() != 0.0)).unwrap_or(0);
+        if degree < 3 {
+            return Err(format!("Rodzina musi być co najmniej trzeciego stopnia w z: {}", text));
+        }
+        coefficients.truncate(degree + 1);
+        let family = ParameterFamily { expression, coefficients };
+
+        // Punkt kontrolny daleko od okręgów próbek: funkcja, która nie jest
+        // wielomianem (np. sin(c)), różni się tam wyraźnie od obciętego
+        // szeregu; tolerancja względem sumy modułów wyrazów
+        let (z, c): (Complex<f64>, Complex<f64>) = (Complex::new(1.3, 1.1), Complex::new(-1.4, 1.2));
+        let bound: f64 = family.coefficients.iter().enumerate()
+            .flat_map(|(k, row)| row.iter().enumerate().map(move |(m, a)| a.norm() * z.norm().powi(k as i32) * c.norm().powi(m as i32)))
+            .sum();
+        let expected = family.expression.eval_family(z, c);
+        let actual = family.polynomial(c).map(|p| p.eval_with_derivative(z).0);
+        if actual.is_none_or(|actual| (actual - expected).norm() > 1e-10 * bound) {
+            return Err(format!("Rodzina musi być wielomianem stopnia mniejszego niż {} w z i w c: {}", SAMPLES, text));
+        }
+        Ok(family)
+    }
+
+    // Wielomian dla parametru c; None, gdy ma stopień zerowy
+    pub fn polynomial(&self, c: Complex<f64>) -> Option<Polynomial> {
+        let coefficients = self.coefficients
+            .iter()
+            .rev()
+            .map(|row| row.iter().rev().fold(Complex::new(0.0, 0.0), |acc, &a| acc * c + a))
+            .collect();
+        Polynomial::new(coefficients).ok()
+    }
+}
+
+impl fmt::Display for ParameterFamily {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+// Kolor parametru c. Każdy przyciągający cykl odwzorowania Newtona, który
+// nie jest pierwiastkiem, przyciąga któryś z jego swobodnych punktów
+// krytycznych, czyli zer p''. Iterujemy więc z każdego z nich: brak
+// zbieżności choć jednego to porażka metody (czarny), w przeciwnym razie
+// kolor pierwiastka, do którego trafia pierwszy z nich.
+fn parameter_color(family: &ParameterFamily, method: Iteration, c: Complex<f64>, config: &FractalConfig, coloring: &Coloring) -> (Rgb<u8>, bool) {
+    let failure = (coloring.color(None, 0.0, &[], config.max_iter), false);
+    let Some(polynomial) = family.polynomial(c) else {
+        return failure;
+    };
+    let Ok(second) = polynomial.derivative().and_then(|d| d.derivative()) else {
+        return failure;
+    };
+    let (Ok(roots), Ok(critical_points)) = (polynomial.roots(), second.roots()) else {
+        return failure;
+    };
+
+    let mut first = None;
+    for critical in critical_points {
+        let result = classify_point(&polynomial, method, &roots, critical, config);
+        if result.root.is_none() {
+            return failure;
+        }
+        first.get_or_insert(result);
+    }
+    match first {
+        Some(result) => (coloring.color(result.root, result.smooth, &roots, config.max_iter), true),
+        None => failure,
+    }
+}
+
+// Płaszczyzna parametru: piksel to wartość c, a nie punkt startowy.
+// Renderowanie jak w `render_fractal`, pasami wierszy w wątkach rayon.
+pub fn render_parameter_plane(family: &ParameterFamily, method: Iteration, config: &FractalConfig, coloring: &Coloring, output: &str) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Rodzina: {}", family);
+    println!("Metoda: {}", method.label());
+    println!("Generowanie płaszczyzny parametru...");
+    let start = Instant::now();
+
+    let width = config.width as usize;
+    let mut buffer = vec![0u8; width * 3 * config.height as usize];
+    let converged: usize = buffer
+        .par_chunks_mut(width * 3 * STRIP_ROWS)
+        .enumerate()
+        .map(|(strip, rows)| {
+            let mut converged = 0;
+            for (offset, row) in rows.chunks_mut(width * 3).enumerate() {
+                let i = (strip * STRIP_ROWS + offset) as u32;
+                for (j, pixel) in row.chunks_mut(3).enumerate() {
+                    let c = pixel_to_complex::<f64>(config, j as u32, i);
+                    let (color, success) = parameter_color(family, method, c, config, coloring);
+                    pixel.copy_from_slice(&color.0);
+                    converged += success as usize;
+                }
+            }
+            converged
+        })
+        .sum();
+
+    println!("Płaszczyzna parametru wygenerowana w czasie: {:?}", start.elapsed());
+    let total = width * config.height as usize;
+    println!("Parametry, dla których metoda zbiega: {:.2}% ({} z {})",
+             100.0 * converged as f64 / total as f64, converged, total);
+    RgbImage::from_raw(config.width, config.height, buffer).unwrap().save(output)?;
+    println!("Płaszczyzna parametru zapisana jako {}", output);
+    Ok(())
+}
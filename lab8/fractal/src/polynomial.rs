@@ -0,0 +1,477 @@
+use num_complex::Complex;
+use crate::double_double::DoubleDouble;
+use crate::iteration::ComplexFunction;
+use std::fmt;
+
+// Największy dopuszczalny stopień; wykładnik z tekstu wyznacza rozmiar
+// tablicy współczynników
+const MAX_DEGREE: usize = 1000;
+
+// Wielomian zespolony; współczynniki od najwyższej potęgi
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polynomial {
+    coefficients: Vec<Complex<f64>>,
+}
+
+impl Polynomial {
+    // Zera na początku (przy najwyższych potęgach) są pomijane; wielomian
+    // stały nie ma pierwiastków, więc go odrzucamy
+    pub fn new(coefficients: Vec<Complex<f64>>) -> Result<Polynomial, String> {
+        let first = coefficients.iter().position(|c| c.norm() != 0.0);
+        match first {
+            Some(first) if first + 1 < coefficients.len() => {
+                Ok(Polynomial { coefficients: coefficients[first..].to_vec() })
+            }
+            _ => Err("Wielomian musi być co najmniej pierwszego stopnia".to_string()),
+        }
+    }
+
+    // Lista współczynników rozdzielonych przecinkami, od najwyższej potęgi,
+    // np. "1, 0, 2+i, -1"
+    pub fn from_coefficient_list(text: &str) -> Result<Polynomial, String> {
+        let coefficients = text
+            .split(',')
+            .map(|item| {
+                let constant = Polynomial::parse_terms(item)?;
+                match constant[..] {
+                    [c] => Ok(c),
+                    _ => Err(format!("Współczynnik nie może zawierać z: {}", item.trim())),
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Polynomial::new(coefficients)
+    }
+
+    // Wielomian zapisany tekstem, np. "z^5 - 3z^2 + 1+2i" albo "(1-i)*z^3 + iz"
+    pub fn parse(text: &str) -> Result<Polynomial, String> {
+        Polynomial::new(Polynomial::parse_terms(text)?)
+    }
+
+    // Rozkład tekstu na współczynniki (od najwyższej potęgi); wyrazy o tej
+    // samej potędze są sumowane
+    fn parse_terms(text: &str) -> Result<Vec<Complex<f64>>, String> {
+        let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+        if chars.is_empty() {
+            return Err("Pusty wielomian".to_string());
+        }
+
+        let mut by_power: Vec<Complex<f64>> = Vec::new();
+        let mut pos = 0;
+        while pos < chars.len() {
+            let sign = match chars[pos] {
+                '+' => { pos += 1; 1.0 }
+                '-' => { pos += 1; -1.0 }
+                _ if pos == 0 => 1.0,
+                c => return Err(format!("Oczekiwano + lub - zamiast '{}' w: {}", c, text)),
+            };
+            let (coefficient, power) = parse_term(&chars, &mut pos)
+                .map_err(|e| format!("{} w: {}", e, text))?;
+            if power > MAX_DEGREE {
+                return Err(format!("Stopień {} przekracza dopuszczalny {} w: {}", power, MAX_DEGREE, text));
+            }
+            if by_power.len() <= power {
+                by_power.resize(power + 1, Complex::new(0.0, 0.0));
+            }
+            by_power[power] += coefficient * sign;
+        }
+
+        by_power.reverse();
+        Ok(by_power)
+    }
+
+    pub fn degree(&self) -> usize {
+        self.coefficients.len() - 1
+    }
+
+    // Pochodna; dla wielomianu pierwszego stopnia błąd, bo wynik jest stały
+    pub fn derivative(&self) -> Result<Polynomial, String> {
+        let degree = self.degree();
+        Polynomial::new(self.coefficients[..degree]
+            .iter()
+            .enumerate()
+            .map(|(k, &c)| c * (degree - k) as f64)
+            .collect())
+    }
+
+    // Wartość wielomianu i jego pochodnej w punkcie (schemat Hornera)
+    pub fn eval_with_derivative(&self, z: Complex<f64>) -> (Complex<f64>, Complex<f64>) {
+        let mut p = Complex::new(0.0, 0.0);
+        let mut dp = Complex::new(0.0, 0.0);
+        for &c in &self.coefficients {
+            dp = dp * z + p;
+            p = p * z + c;
+        }
+        (p, dp)
+    }
+
+    // Pierwiastki wyznaczane metodą Abertha-Ehrlicha. Wynik jest posortowany
+    // (wg argumentu, potem modułu), więc kolejność - a z nią kolory - nie
+    // zależy od przebiegu. Błąd, gdy pierwiastków nie da się wyznaczyć
+    // w zakresie f64.
+    pub fn roots(&self) -> Result<Vec<Complex<f64>>, String> {
+        let lead = self.coefficients[0];
+        let degree = self.degree();
+
+        // Podstawienie z = s w, gdzie s = max |a_k / a_0|^(1/k): wielomian
+        // w w ma współczynniki o modułach co najwyżej 1, więc np. dla
+        // z^2 + 1e200 iteracja nie przekracza zakresu f64. Dzielimy
+        // k-krotnie przez s zamiast przez s^k, które mogłoby się przepełnić.
+        let monic: Vec<Complex<f64>> = self.coefficients.iter().map(|&c| c / lead).collect();
+        let scale = monic[1..].iter()
+            .enumerate()
+            .map(|(k, c)| c.norm().powf(1.0 / (k + 1) as f64))
+            .fold(0.0, f64::max);
+        if !scale.is_finite() {
+            return Err(format!("Nie można wyznaczyć pierwiastków wielomianu {}", self));
+        }
+        let scale = if scale == 0.0 { 1.0 } else { scale };
+        let scaled = Polynomial {
+            coefficients: monic.iter()
+                .enumerate()
+                .map(|(k, &c)| (0..k).fold(c, |c, _| c / scale))
+                .collect(),
+        };
+
+        // Punkty startowe na okręgu o promieniu ograniczającym moduły pierwiastków
+        // (oszacowanie Cauchy'ego), lekko obrócone, żeby uniknąć symetrii
+        let radius = 1.0 + scaled.coefficients[1..].iter().map(|c| c.norm()).fold(0.0, f64::max);
+        let mut roots: Vec<Complex<f64>> = (0..degree)
+            .map(|k| Complex::from_polar(radius, 2.0 * std::f64::consts::PI * k as f64 / degree as f64 + 0.4))
+            .collect();
+
+        for _ in 0..500 {
+            let mut max_step: f64 = 0.0;
+            for i in 0..degree {
+                let (p, dp) = scaled.eval_with_derivative(roots[i]);
+                if p.norm() == 0.0 {
+                    continue;
+                }
+                let ratio = p / dp;
+                let repulsion: Complex<f64> = (0..degree)
+                    .filter(|&j| j != i)
+                    .map(|j| Complex::new(1.0, 0.0) / (roots[i] - roots[j]))
+                    .sum();
+                let step = ratio / (Complex::new(1.0, 0.0) - ratio * repulsion);
+                roots[i] -= step;
+                max_step = max_step.max(step.norm());
+            }
+            if max_step < 1e-14 * radius {
+                break;
+            }
+        }
+
+        // Resztki numeryczne w częściach rzeczywistych/urojonych zerujemy,
+        // żeby np. pierwiastek -1 zawsze miał argument π, a nie -π
+        for root in roots.iter_mut() {
+            if root.re.abs() < 1e-12 * radius {
+                root.re = 0.0;
+            }
+            if root.im.abs() < 1e-12 * radius {
+                root.im = 0.0;
+            }
+            *root *= scale;
+        }
+        if roots.iter().any(|r| !r.re.is_finite() || !r.im.is_finite()) {
+            return Err(format!("Nie można wyznaczyć pierwiastków wielomianu {}", self));
+        }
+
+        roots.sort_by(|a, b| a.arg().total_cmp(&b.arg()).then(a.norm().total_cmp(&b.norm())));
+        Ok(roots)
+    }
+}
+
+// Jeden wyraz: [współczynnik][*][z[^n]]. Współczynnik to liczba rzeczywista,
+// urojona ("2i", "i") albo zespolona w nawiasie ("(1-2i)").
+fn parse_term(chars: &[char], pos: &mut usize) -> Result<(Complex<f64>, usize), String> {
+    let start = *pos;
+    let mut coefficient = None;
+
+    if chars.get(*pos) == Some(&'(') {
+        let close = chars[*pos..].iter().position(|&c| c == ')')
+            .ok_or("Brak zamykającego nawiasu")?;
+        let inner: String = chars[*pos + 1..*pos + close].iter().collect();
+        coefficient = match Polynomial::parse_terms(&inner)?[..] {
+            [c] => Some(c),
+            _ => return Err(format!("Współczynnik w nawiasie nie może zawierać z: ({})", inner)),
+        };
+        *pos += close + 1;
+    } else if chars.get(*pos).is_some_and(|c| c.is_ascii_digit() || *c == '.') {
+        let number_start = *pos;
+        while *pos < chars.len() {
+            let c = chars[*pos];
+            let exponent = (c == 'e' || c == 'E')
+                && chars.get(*pos + 1).is_some_and(|n| n.is_ascii_digit() || *n == '-' || *n == '+');
+            if c.is_ascii_digit() || c == '.' {
+                *pos += 1;
+            } else if exponent {
+                *pos += 2;
+            } else {
+                break;
+            }
+        }
+        let text: String = chars[number_start..*pos].iter().collect();
+        let value: f64 = text.parse().map_err(|_| format!("Niepoprawna liczba '{}'", text))?;
+        coefficient = Some(Complex::new(value, 0.0));
+    }
+
+    // Jednostka urojona: sama ("i") albo po liczbie ("2i")
+    if chars.get(*pos) == Some(&'i') {
+        coefficient = Some(coefficient.unwrap_or(Complex::new(1.0, 0.0)) * Complex::new(0.0, 1.0));
+        *pos += 1;
+    }
+
+    if coefficient.is_some() && chars.get(*pos) == Some(&'*') {
+        *pos += 1;
+        if chars.get(*pos) != Some(&'z') {
+            return Err("Oczekiwano z po '*'".to_string());
+        }
+    }
+
+    let mut power = 0;
+    if chars.get(*pos) == Some(&'z') {
+        *pos += 1;
+        power = 1;
+        if chars.get(*pos) == Some(&'^') {
+            *pos += 1;
+            let digits_start = *pos;
+            while chars.get(*pos).is_some_and(|c| c.is_ascii_digit()) {
+                *pos += 1;
+            }
+            let text: String = chars[digits_start..*pos].iter().collect();
+            power = text.parse().map_err(|_| "Oczekiwano wykładnika po '^'".to_string())?;
+        }
+    } else if coefficient.is_none() {
+        return match chars.get(start) {
+            Some(c) => Err(format!("Nieoczekiwany znak '{}'", c)),
+            None => Err("Brak wyrazu na końcu wielomianu".to_string()),
+        };
+    }
+
+    Ok((coefficient.unwrap_or(Complex::new(1.0, 0.0)), power))
+}
+
+impl ComplexFunction for Polynomial {
+    fn value_and_derivative(&self, z: Complex<f64>) -> (Complex<f64>, Complex<f64>) {
+        self.eval_with_derivative(z)
+    }
+
+    // Rozszerzony schemat Hornera: t[k] = p^(k)(z) / k!
+    fn derivatives(&self, z: Complex<f64>) -> [Complex<f64>; 4] {
+        let mut t = [Complex::new(0.0, 0.0); 4];
+        for &c in &self.coefficients {
+            for k in (1..4).rev() {
+                t[k] = t[k] * z + t[k - 1];
+            }
+            t[0] = t[0] * z + c;
+        }
+        [t[0], t[1], 2.0 * t[2], 6.0 * t[3]]
+    }
+
+    // Ten sam schemat w double-double; współczynniki są dokładne w f64
+    fn derivatives_dd(&self, z: Complex<DoubleDouble>) -> Option<[Complex<DoubleDouble>; 4]> {
+        let zero = Complex::new(DoubleDouble::new(0.0), DoubleDouble::new(0.0));
+        let mut t = [zero; 4];
+        for c in &self.coefficients {
+            for k in (1..4).rev() {
+                t[k] = t[k] * z + t[k - 1];
+            }
+            t[0] = t[0] * z + Complex::new(DoubleDouble::new(c.re), DoubleDouble::new(c.im));
+        }
+        Some([t[0], t[1], t[2] * DoubleDouble::new(2.0), t[3] * DoubleDouble::new(6.0)])
+    }
+
+    fn value_and_derivative_dd(&self, z: Complex<DoubleDouble>) -> Option<(Complex<DoubleDouble>, Complex<DoubleDouble>)> {
+        let zero = Complex::new(DoubleDouble::new(0.0), DoubleDouble::new(0.0));
+        let (mut p, mut dp) = (zero, zero);
+        for c in &self.coefficients {
+            dp = dp * z + p;
+            p = p * z + Complex::new(DoubleDouble::new(c.re), DoubleDouble::new(c.im));
+        }
+        Some((p, dp))
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let degree = self.degree();
+        let mut first = true;
+        for (k, c) in self.coefficients.iter().enumerate() {
+            if c.norm() == 0.0 {
+                continue;
+            }
+            let power = degree - k;
+            // Ujemne współczynniki rzeczywiste zapisujemy jako odejmowanie
+            let (sign, c) = if c.im == 0.0 && c.re < 0.0 { ("-", -c) } else { ("+", *c) };
+            match (first, sign) {
+                (true, "-") => write!(f, "-")?,
+                (true, _) => {}
+                (false, sign) => write!(f, " {} ", sign)?,
+            }
+            first = false;
+            // Współczynnik pomijamy tylko wtedy, gdy jest równy 1 i stoi przy z
+            let unit = c == Complex::new(1.0, 0.0) && power > 0;
+            if !unit {
+                if c.im == 0.0 {
+                    write!(f, "{}", c.re)?;
+                } else {
+                    write!(f, "({})", c)?;
+                }
+            }
+            match power {
+                0 => {}
+                1 => write!(f, "z")?,
+                _ => write!(f, "z^{}", power)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(re: f64, im: f64) -> Complex<f64> {
+        Complex::new(re, im)
+    }
+
+    #[test]
+    fn parses_terms_in_any_order() {
+        let p = Polynomial::parse("1+2i - 3z^2 + z^5").unwrap();
+        assert_eq!(p.coefficients, vec![c(1.0, 0.0), c(0.0, 0.0), c(0.0, 0.0), c(-3.0, 0.0), c(0.0, 0.0), c(1.0, 2.0)]);
+    }
+
+    #[test]
+    fn parses_complex_coefficients_and_sums_equal_powers() {
+        let p = Polynomial::parse("(1-i)*z^3 + iz + 2z - 2.5e-1").unwrap();
+        assert_eq!(p.coefficients, vec![c(1.0, -1.0), c(0.0, 0.0), c(2.0, 1.0), c(-0.25, 0.0)]);
+    }
+
+    #[test]
+    fn parses_coefficient_list() {
+        let p = Polynomial::from_coefficient_list("0, 1, 0, 2+i, -1").unwrap();
+        assert_eq!(p.coefficients, vec![c(1.0, 0.0), c(0.0, 0.0), c(2.0, 1.0), c(-1.0, 0.0)]);
+        assert!(Polynomial::from_coefficient_list("1, z").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        for text in ["", "7", "z^", "2*", "z^2 +", "zz", "(1+i z", "z^1001"] {
+            assert!(Polynomial::parse(text).is_err(), "{}", text);
+        }
+        assert_eq!(Polynomial::parse("z^1000 - 1").unwrap().degree(), 1000);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for text in ["z^5 - 1", "z^3 - 3z^2 + 0.5", "(1-1i)z^3 + (0+1i)z", "-z^2 + 2z", "2z^4 + (1+2i)"] {
+            let p = Polynomial::parse(text).unwrap();
+            assert_eq!(Polynomial::parse(&p.to_string()).unwrap(), p, "{}", text);
+        }
+        assert_eq!(Polynomial::parse("z^3 - 3z^2 + 0.5").unwrap().to_string(), "z^3 - 3z^2 + 0.5");
+    }
+
+    #[test]
+    fn derivative_coefficients() {
+        let p = Polynomial::parse("z^3 + 2z^2 - z + 4").unwrap();
+        assert_eq!(p.derivative().unwrap().to_string(), "3z^2 + 4z - 1");
+        assert_eq!(p.derivative().unwrap().derivative().unwrap().to_string(), "6z + 4");
+        assert!(Polynomial::parse("2z + 1").unwrap().derivative().is_err());
+    }
+
+    #[test]
+    fn horner_matches_explicit_derivatives() {
+        let p = Polynomial::parse("(1-i)z^5 + 3z^3 - iz + 2").unwrap();
+        let d1 = p.derivative().unwrap();
+        let d2 = d1.derivative().unwrap();
+        let d3 = d2.derivative().unwrap();
+        let z = c(0.7, -1.3);
+        let (value, derivative) = p.eval_with_derivative(z);
+        let expected = [value, d1.eval_with_derivative(z).0, d2.eval_with_derivative(z).0, d3.eval_with_derivative(z).0];
+        assert!((derivative - expected[1]).norm() < 1e-12);
+        // Rozszerzony schemat Hornera
+        for (k, (actual, expected)) in p.derivatives(z).iter().zip(expected).enumerate() {
+            assert!((actual - expected).norm() < 1e-12 * expected.norm(), "pochodna rzędu {}", k);
+        }
+    }
+
+    #[test]
+    fn double_double_horner_agrees_with_f64() {
+        let p = Polynomial::parse("z^4 - 2z + 1").unwrap();
+        let z = c(0.3, 0.4);
+        let dd = Complex::new(DoubleDouble::new(z.re), DoubleDouble::new(z.im));
+        let (value, derivative) = p.value_and_derivative_dd(dd).unwrap();
+        let (expected, expected_derivative) = p.eval_with_derivative(z);
+        assert!((c(value.re.to_f64(), value.im.to_f64()) - expected).norm() < 1e-15);
+        assert!((c(derivative.re.to_f64(), derivative.im.to_f64()) - expected_derivative).norm() < 1e-15);
+    }
+
+    // |p(r)| w stosunku do Σ |a_k| |r|^k - błąd wsteczny niezależny od skali współczynników
+    fn assert_residuals_small(p: &Polynomial, roots: &[Complex<f64>], tol: f64) {
+        assert_eq!(roots.len(), p.degree(), "{}", p);
+        for &root in roots {
+            let (value, _) = p.eval_with_derivative(root);
+            let magnitude = p.coefficients.iter().fold(0.0, |acc, a| acc * root.norm() + a.norm());
+            assert!(value.norm() <= tol * magnitude, "{}: |p({})| = {:e}", p, root, value.norm());
+        }
+    }
+
+    #[test]
+    fn roots_of_unity_in_argument_order() {
+        for n in [2, 3, 5, 8, 13] {
+            let p = Polynomial::parse(&format!("z^{} - 1", n)).unwrap();
+            let roots = p.roots().unwrap();
+            assert_residuals_small(&p, &roots, 1e-14);
+
+            // Oczekiwana kolejność: rosnący argument z przedziału (-π, π]
+            let mut expected: Vec<Complex<f64>> = (0..n)
+                .map(|k| Complex::from_polar(1.0, 2.0 * std::f64::consts::PI * k as f64 / n as f64))
+                .collect();
+            expected.sort_by(|a, b| a.arg().total_cmp(&b.arg()));
+            for (root, expected) in roots.iter().zip(&expected) {
+                assert!((root - expected).norm() < 1e-12, "z^{} - 1: {} zamiast {}", n, root, expected);
+            }
+            assert_eq!(p.roots().unwrap(), roots, "z^{} - 1: kolejność się zmienia", n);
+        }
+    }
+
+    #[test]
+    fn roots_with_huge_and_tiny_coefficients() {
+        // Bez skalowania z^2 + 1e200 przepełnia f64 już w pierwszym kroku
+        let cases = [
+            ("z^2 + 1e200", vec![c(0.0, -1e100), c(0.0, 1e100)]),
+            ("z^2 - 1e-200", vec![c(1e-100, 0.0), c(-1e-100, 0.0)]),
+            ("1e-150*z^2 - 1e-150", vec![c(1.0, 0.0), c(-1.0, 0.0)]),
+            ("1e100*z^3 - 8e100", vec![c(2.0, 0.0)]),
+        ];
+        for (text, expected) in cases {
+            let p = Polynomial::parse(text).unwrap();
+            let roots = p.roots().unwrap();
+            assert_residuals_small(&p, &roots, 1e-14);
+            for expected in expected {
+                assert!(
+                    roots.iter().any(|r| (r - expected).norm() < 1e-12 * expected.norm()),
+                    "{}: brak pierwiastka {} w {:?}", text, expected, roots
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn repeated_roots() {
+        // (z - 1)^3: pierwiastek potrójny jest wyznaczalny tylko z dokładnością ~ε^(1/3)
+        let p = Polynomial::parse("z^3 - 3z^2 + 3z - 1").unwrap();
+        let roots = p.roots().unwrap();
+        assert_residuals_small(&p, &roots, 1e-14);
+        for root in &roots {
+            assert!((root - c(1.0, 0.0)).norm() < 1e-4, "{}", root);
+        }
+
+        // (z - 1)^2 (z + 2): podwójny i pojedynczy
+        let p = Polynomial::parse("z^3 - 3z + 2").unwrap();
+        let roots = p.roots().unwrap();
+        assert_residuals_small(&p, &roots, 1e-14);
+        assert_eq!(roots.iter().filter(|r| (*r - c(1.0, 0.0)).norm() < 1e-6).count(), 2, "{:?}", roots);
+        assert_eq!(roots.iter().filter(|r| (*r - c(-2.0, 0.0)).norm() < 1e-12).count(), 1, "{:?}", roots);
+    }
+}
@@ -0,0 +1,191 @@
+use image::RgbImage;
+use num_complex::Complex;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use crate::iteration::{ComplexFunction, Iteration};
+use crate::palette::Coloring;
+use crate::{precision_label, render_rows, FractalConfig};
+
+// Bok kafelka piramidy; pas renderowanych wierszy ma tę samą wysokość, więc
+// każdy pas daje pełny rząd kafelków najwyższego poziomu
+const TILE_SIZE: usize = 256;
+
+// Renderuje obraz pasami po TILE_SIZE wierszy i od razu zapisuje je do
+// pliku PNG, a opcjonalnie także do piramidy kafelków Deep Zoom
+// (`tiles`.dzi i katalog `tiles`_files, np. dla OpenSeadragon). W pamięci
+// jest tylko bieżący pas i po jednym niepełnym rzędzie kafelków na każdy
+// poziom piramidy, więc zużycie pamięci zależy od szerokości obrazu, a nie
+// od jego wysokości.
+pub fn render_poster<F>(function: &F, method: Iteration, roots: &[Complex<f64>], config: &FractalConfig, coloring: &Coloring, output: &str, tiles: Option<&str>) -> Result<(), Box<dyn Error>>
+where
+    F: ComplexFunction + ?Sized,
+{
+    let (width, height) = (config.width as usize, config.height as usize);
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(output)?), config.width, config.height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    let mut stream = writer.stream_writer()?;
+    let mut pyramid = tiles.map(|name| TilePyramid::new(name, width, height)).transpose()?;
+
+    println!("Metoda: {}", method.label());
+    println!("Arytmetyka: {}", precision_label(function, config));
+    if config.sampling.samples > 1 {
+        println!("Próbkowanie: {}", config.sampling.label());
+    }
+    println!("Generowanie plakatu {}x{} pasami po {} wierszy...", width, height, TILE_SIZE);
+    let start = Instant::now();
+    let bands = height.div_ceil(TILE_SIZE);
+    let mut converged = 0;
+    for band in 0..bands {
+        let rows = band * TILE_SIZE..((band + 1) * TILE_SIZE).min(height);
+        let (buffer, map) = render_rows(function, method, roots, config, coloring, rows);
+        converged += map.converged();
+        stream.write_all(&buffer)?;
+        if let Some(pyramid) = pyramid.as_mut() {
+            for row in buffer.chunks(width * 3) {
+                pyramid.push_row(pyramid.levels.len() - 1, row.to_vec())?;
+            }
+        }
+        println!("Pas {:>5}/{} ({:.1?})", band + 1, bands, start.elapsed());
+    }
+    stream.finish()?;
+
+    println!("Plakat wygenerowany w czasie: {:?}", start.elapsed());
+    println!("Skuteczność zbieżności: {:.2}% (zbiegło {} z {} pikseli)",
+             100.0 * converged as f64 / (width * height) as f64, converged, width * height);
+    println!("Plakat zapisany jako {}", output);
+    if let Some(pyramid) = pyramid {
+        let descriptor = pyramid.finish()?;
+        println!("Piramida kafelków zapisana jako {}", descriptor.display());
+    }
+    Ok(())
+}
+
+// Poziom piramidy: niepełny rząd kafelków i wiersz czekający na parę do
+// pomniejszenia
+struct Level {
+    width: usize,
+    height: usize,
+    // Wiersze bieżącego rzędu kafelków
+    pending: Vec<u8>,
+    // Numer pierwszego z nich
+    first_row: usize,
+    carry: Option<Vec<u8>>,
+}
+
+// Piramida Deep Zoom: poziom L ma rozmiar ceil(w / 2^(max - L)), od 1x1 na
+// poziomie 0 do pełnego obrazu na poziomie max. Każdy poziom powstaje
+// z wyższego przez uśrednianie bloków 2x2, w miarę napływania wierszy.
+struct TilePyramid {
+    directory: PathBuf,
+    descriptor: PathBuf,
+    levels: Vec<Level>,
+}
+
+impl TilePyramid {
+    fn new(name: &str, width: usize, height: usize) -> Result<TilePyramid, Box<dyn Error>> {
+        let base = Path::new(name).with_extension("");
+        let directory = PathBuf::from(format!("{}_files", base.display()));
+        let max_level = (width.max(height) as f64).log2().ceil() as usize;
+        let mut levels = Vec::with_capacity(max_level + 1);
+        for level in 0..=max_level {
+            let scale = 1usize << (max_level - level);
+            std::fs::create_dir_all(directory.join(level.to_string()))?;
+            levels.push(Level {
+                width: width.div_ceil(scale),
+                height: height.div_ceil(scale),
+                pending: Vec::new(),
+                first_row: 0,
+                carry: None,
+            });
+        }
+
+        let descriptor = base.with_extension("dzi");
+        std::fs::write(&descriptor, format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <Image xmlns=\"http://schemas.microsoft.com/deepzoom/2008\" TileSize=\"{}\" Overlap=\"0\" Format=\"png\">\n  \
+             <Size Width=\"{}\" Height=\"{}\"/>\n\
+             </Image>\n",
+            TILE_SIZE, width, height))?;
+        Ok(TilePyramid { directory, descriptor, levels })
+    }
+
+    // Dopisuje wiersz do poziomu `level`; co drugi wiersz, razem
+    // z poprzednim, daje wiersz poziomu niższego
+    fn push_row(&mut self, level: usize, row: Vec<u8>) -> Result<(), Box<dyn Error>> {
+        let current = &mut self.levels[level];
+        current.pending.extend_from_slice(&row);
+        if current.pending.len() == current.width * 3 * TILE_SIZE {
+            self.write_tiles(level)?;
+        }
+        if level == 0 {
+            return Ok(());
+        }
+        let current = &mut self.levels[level];
+        match current.carry.take() {
+            Some(previous) => {
+                let down = downsample(&previous, &row, current.width);
+                self.push_row(level - 1, down)
+            }
+            None => {
+                current.carry = Some(row);
+                Ok(())
+            }
+        }
+    }
+
+    // Zapisuje rząd kafelków z wierszy oczekujących na poziomie `level`
+    fn write_tiles(&mut self, level: usize) -> Result<(), Box<dyn Error>> {
+        let current = &mut self.levels[level];
+        let rows = current.pending.len() / (current.width * 3);
+        let tile_row = current.first_row / TILE_SIZE;
+        for (column, x) in (0..current.width).step_by(TILE_SIZE).enumerate() {
+            let tile_width = TILE_SIZE.min(current.width - x);
+            let mut tile = Vec::with_capacity(tile_width * rows * 3);
+            for row in current.pending.chunks(current.width * 3) {
+                tile.extend_from_slice(&row[x * 3..(x + tile_width) * 3]);
+            }
+            let tile = RgbImage::from_raw(tile_width as u32, rows as u32, tile).unwrap();
+            tile.save(self.directory.join(level.to_string()).join(format!("{}_{}.png", column, tile_row)))?;
+        }
+        current.first_row += rows;
+        current.pending.clear();
+        Ok(())
+    }
+
+    // Domyka piramidę od najwyższego poziomu: wiersz bez pary (przy
+    // nieparzystej wysokości) pomniejszamy sam ze sobą, a niepełny rząd
+    // kafelków zapisujemy
+    fn finish(mut self) -> Result<PathBuf, Box<dyn Error>> {
+        for level in (0..self.levels.len()).rev() {
+            if let Some(row) = self.levels[level].carry.take() {
+                let down = downsample(&row, &row, self.levels[level].width);
+                self.push_row(level - 1, down)?;
+            }
+            if !self.levels[level].pending.is_empty() {
+                self.write_tiles(level)?;
+            }
+            debug_assert_eq!(self.levels[level].first_row, self.levels[level].height);
+        }
+        Ok(self.descriptor)
+    }
+}
+
+// Wiersz o połowę węższy: średnie z bloków 2x2 (ostatnia kolumna przy
+// nieparzystej szerokości jest powielana)
+fn downsample(upper: &[u8], lower: &[u8], width: usize) -> Vec<u8> {
+    let mut row = Vec::with_capacity(width.div_ceil(2) * 3);
+    for x in (0..width).step_by(2) {
+        let right = (x + 1).min(width - 1);
+        for c in 0..3 {
+            let sum = upper[x * 3 + c] as u32 + upper[right * 3 + c] as u32
+                + lower[x * 3 + c] as u32 + lower[right * 3 + c] as u32;
+            row.push(((sum + 2) / 4) as u8);
+        }
+    }
+    row
+}
@@ -0,0 +1,56 @@
+// Próbkowanie pikseli (antyaliasing)
+#[derive(Clone, Copy, Debug)]
+pub struct Sampling {
+    // Próbki na bok piksela: siatka N x N, kolory są uśredniane
+    pub samples: u32,
+    // Próbkowanie warstwowe: każda próbka w losowym miejscu swojej komórki
+    // siatki zamiast w jej środku
+    pub jitter: bool,
+    // Siatka próbek tylko w pikselach, których basen różni się od basenu
+    // któregoś z sąsiadów; pozostałe piksele mają jedną próbkę w środku
+    pub adaptive: bool,
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Sampling { samples: 1, jitter: false, adaptive: false }
+    }
+}
+
+impl Sampling {
+    pub fn label(&self) -> String {
+        let mut label = format!("{}x{}", self.samples, self.samples);
+        if self.jitter {
+            label += ", warstwowe";
+        }
+        if self.adaptive {
+            label += ", adaptacyjne";
+        }
+        label
+    }
+
+    // Położenia próbek w pikselu (wiersz i, kolumna j) względem jego lewego
+    // górnego rogu, w jednostkach piksela. Rozrzut zależy tylko od numeru
+    // piksela i próbki, więc obraz jest powtarzalny.
+    pub fn offsets(&self, i: u32, j: u32) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let n = self.samples;
+        (0..n * n).map(move |k| {
+            let (u, v) = if self.jitter {
+                let seed = ((i as u64) << 32 | j as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ k as u64;
+                (unit(seed), unit(seed ^ 0xD1B5_4A32_D192_ED03))
+            } else {
+                (0.5, 0.5)
+            };
+            (((k % n) as f64 + u) / n as f64, ((k / n) as f64 + v) / n as f64)
+        })
+    }
+}
+
+// Liczba z [0, 1) wyznaczona z ziarna (mieszanie splitmix64)
+fn unit(seed: u64) -> f64 {
+    let mut x = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^= x >> 31;
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,323 @@
+use num_complex::Complex;
+use serde::Serialize;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::path::Path;
+use crate::FractalConfig;
+
+// Etykieta pikseli, które nie zbiegły do żadnego pierwiastka
+pub const NO_ROOT: u16 = u16::MAX;
+
+// Wynik renderowania piksel po pikselu: numer pierwiastka i liczba kroków
+pub struct BasinMap {
+    pub width: usize,
+    pub height: usize,
+    pub labels: Vec<u16>,
+    pub steps: Vec<u32>,
+}
+
+impl BasinMap {
+    pub fn new(width: usize, height: usize) -> BasinMap {
+        BasinMap { width, height, labels: vec![NO_ROOT; width * height], steps: vec![0; width * height] }
+    }
+
+    pub fn converged(&self) -> usize {
+        self.labels.iter().filter(|&&label| label != NO_ROOT).count()
+    }
+
+    // Mapa ograniczona do wierszy `rows`
+    pub fn rows(self, rows: Range<usize>) -> BasinMap {
+        if rows == (0..self.height) {
+            return self;
+        }
+        let (start, end) = (rows.start * self.width, rows.end * self.width);
+        BasinMap {
+            width: self.width,
+            height: rows.len(),
+            labels: self.labels[start..end].to_vec(),
+            steps: self.steps[start..end].to_vec(),
+        }
+    }
+
+    // Czy piksel (wiersz i, kolumna j) ma sąsiada z innym basenem
+    // (sąsiedztwo 8 pikseli)
+    pub fn is_edge(&self, i: usize, j: usize) -> bool {
+        let label = self.labels[i * self.width + j];
+        let rows = i.saturating_sub(1)..(i + 2).min(self.height);
+        rows.flat_map(|y| (j.saturating_sub(1)..(j + 2).min(self.width)).map(move |x| (y, x)))
+            .any(|(y, x)| self.labels[y * self.width + x] != label)
+    }
+
+    // Piksele brzegowe: etykieta różna od sąsiada z prawej albo z dołu
+    fn boundary(&self) -> Vec<(usize, usize)> {
+        let mut boundary = Vec::new();
+        for i in 0..self.height {
+            for j in 0..self.width {
+                let label = self.labels[i * self.width + j];
+                let right = j + 1 < self.width && self.labels[i * self.width + j + 1] != label;
+                let below = i + 1 < self.height && self.labels[(i + 1) * self.width + j] != label;
+                if right || below {
+                    boundary.push((i, j));
+                }
+            }
+        }
+        boundary
+    }
+}
+
+// Format pliku ze statystykami wg rozszerzenia: .json albo .csv
+// (długi format: statystyka, indeks, wartość)
+pub enum StatsFormat {
+    Json,
+    Csv,
+}
+
+impl StatsFormat {
+    pub fn from_filename(filename: &str) -> Result<StatsFormat, String> {
+        let extension = Path::new(filename).extension().and_then(|e| e.to_str()).unwrap_or("");
+        match extension.to_lowercase().as_str() {
+            "json" => Ok(StatsFormat::Json),
+            "csv" => Ok(StatsFormat::Csv),
+            _ => Err(format!("Nieobsługiwany format statystyk (json albo csv): {}", filename)),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Basin {
+    pub root_re: f64,
+    pub root_im: f64,
+    pub pixels: usize,
+    pub fraction: f64,
+    // Pole basenu w jednostkach płaszczyzny zespolonej
+    pub area: f64,
+}
+
+#[derive(Serialize)]
+pub struct BoxCount {
+    pub size: usize, // bok pudełka w pikselach
+    pub boxes: usize,
+}
+
+#[derive(Serialize)]
+pub struct BasinStatistics {
+    pub function: String,
+    pub method: String,
+    pub width: usize,
+    pub height: usize,
+    pub xlims: (f64, f64),
+    pub ylims: (f64, f64),
+    pub max_iter: u32,
+    pub basins: Vec<Basin>,
+    pub non_converged_fraction: f64,
+    // Liczba pikseli zbieżnych po k krokach (indeks k)
+    pub iteration_histogram: Vec<usize>,
+    pub box_counts: Vec<BoxCount>,
+    pub boundary_dimension: f64,
+}
+
+impl BasinStatistics {
+    pub fn compute(map: &BasinMap, roots: &[Complex<f64>], config: &FractalConfig, function: &str, method: &str) -> BasinStatistics {
+        let total = (map.width * map.height) as f64;
+        let plane_area = config.span.0 * config.span.1;
+
+        let mut pixels = vec![0usize; roots.len()];
+        let mut histogram = vec![0usize; config.max_iter as usize + 1];
+        for (&label, &steps) in map.labels.iter().zip(&map.steps) {
+            if label != NO_ROOT {
+                pixels[label as usize] += 1;
+                histogram[steps as usize] += 1;
+            }
+        }
+        let basins = roots.iter()
+            .zip(&pixels)
+            .map(|(root, &count)| Basin {
+                root_re: root.re,
+                root_im: root.im,
+                pixels: count,
+                fraction: count as f64 / total,
+                area: count as f64 / total * plane_area,
+            })
+            .collect();
+        let converged: usize = pixels.iter().sum();
+
+        let box_counts = box_counts(map);
+        BasinStatistics {
+            function: function.to_string(),
+            method: method.to_string(),
+            width: map.width,
+            height: map.height,
+            xlims: config.xlims(),
+            ylims: config.ylims(),
+            max_iter: config.max_iter,
+            basins,
+            non_converged_fraction: 1.0 - converged as f64 / total,
+            iteration_histogram: histogram,
+            boundary_dimension: box_counting_dimension(&box_counts),
+            box_counts,
+        }
+    }
+
+    // Średnia liczba kroków wśród pikseli, które zbiegły do pierwiastka
+    pub fn mean_iterations(&self) -> f64 {
+        let converged: usize = self.iteration_histogram.iter().sum();
+        if converged == 0 {
+            return 0.0;
+        }
+        let total: usize = self.iteration_histogram.iter().enumerate().map(|(steps, count)| steps * count).sum();
+        total as f64 / converged as f64
+    }
+
+    pub fn print(&self) {
+        println!("Baseny przyciągania:");
+        for (idx, basin) in self.basins.iter().enumerate() {
+            println!("  {}: {:.6} {:+.6}i  {:>7.3}%  pole {:.4}",
+                     idx, basin.root_re, basin.root_im, 100.0 * basin.fraction, basin.area);
+        }
+        println!("  brak zbieżności: {:.3}%", 100.0 * self.non_converged_fraction);
+        println!("Wymiar pudełkowy brzegu basenów: {:.4}", self.boundary_dimension);
+    }
+
+    pub fn write(&self, filename: &str) -> Result<(), Box<dyn Error>> {
+        let text = match StatsFormat::from_filename(filename)? {
+            StatsFormat::Json => serde_json::to_string_pretty(self)?,
+            StatsFormat::Csv => self.to_csv(),
+        };
+        std::fs::write(filename, text)?;
+        Ok(())
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("statistic,index,value\n");
+        for (idx, basin) in self.basins.iter().enumerate() {
+            writeln!(csv, "root_re,{},{}", idx, basin.root_re).unwrap();
+            writeln!(csv, "root_im,{},{}", idx, basin.root_im).unwrap();
+            writeln!(csv, "basin_pixels,{},{}", idx, basin.pixels).unwrap();
+            writeln!(csv, "basin_fraction,{},{}", idx, basin.fraction).unwrap();
+            writeln!(csv, "basin_area,{},{}", idx, basin.area).unwrap();
+        }
+        writeln!(csv, "non_converged_fraction,,{}", self.non_converged_fraction).unwrap();
+        for (steps, count) in self.iteration_histogram.iter().enumerate() {
+            writeln!(csv, "iteration_histogram,{},{}", steps, count).unwrap();
+        }
+        for count in &self.box_counts {
+            writeln!(csv, "box_count,{},{}", count.size, count.boxes).unwrap();
+        }
+        writeln!(csv, "boundary_dimension,,{}", self.boundary_dimension).unwrap();
+        csv
+    }
+}
+
+// Liczba pudełek o boku 1, 2, 4, ... pikseli (do 1/8 krótszego boku
+// obrazu) zawierających choć jeden piksel brzegowy
+fn box_counts(map: &BasinMap) -> Vec<BoxCount> {
+    let boundary = map.boundary();
+    let mut counts = Vec::new();
+    let mut size = 1;
+    while size <= map.width.min(map.height) / 8 {
+        let columns = map.width.div_ceil(size);
+        let mut occupied = vec![false; columns * map.height.div_ceil(size)];
+        for &(i, j) in &boundary {
+            occupied[(i / size) * columns + j / size] = true;
+        }
+        counts.push(BoxCount { size, boxes: occupied.iter().filter(|&&o| o).count() });
+        size *= 2;
+    }
+    counts
+}
+
+// Nachylenie prostej dopasowanej do ln N(s) względem ln(1/s)
+fn box_counting_dimension(counts: &[BoxCount]) -> f64 {
+    let points: Vec<(f64, f64)> = counts.iter()
+        .filter(|c| c.boxes > 0)
+        .map(|c| (-(c.size as f64).ln(), (c.boxes as f64).ln()))
+        .collect();
+    if points.len() < 2 {
+        return f64::NAN;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let (num, den) = points.iter().fold((0.0, 0.0), |(num, den), &(x, y)| {
+        (num + (x - mean_x) * (y - mean_y), den + (x - mean_x).powi(2))
+    });
+    num / den
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::double_double::DoubleDouble;
+    use crate::sampling::Sampling;
+
+    // Mapa z etykietą zadaną funkcją (wiersz, kolumna); wszystkie piksele po 3 krokach
+    fn map(size: usize, label: impl Fn(usize, usize) -> u16) -> BasinMap {
+        let mut map = BasinMap::new(size, size);
+        for i in 0..size {
+            for j in 0..size {
+                map.labels[i * size + j] = label(i, j);
+                map.steps[i * size + j] = 3;
+            }
+        }
+        map
+    }
+
+    fn config(size: usize) -> FractalConfig {
+        FractalConfig {
+            center: Complex::new(DoubleDouble::new(0.0), DoubleDouble::new(0.0)),
+            span: (2.0, 2.0),
+            width: size as u32,
+            height: size as u32,
+            max_iter: 10,
+            tol: 1e-9,
+            sampling: Sampling::default(),
+        }
+    }
+
+    fn statistics(map: &BasinMap) -> BasinStatistics {
+        let roots = [Complex::new(-1.0, 0.0), Complex::new(1.0, 0.0)];
+        BasinStatistics::compute(map, &roots, &config(map.width), "z^2 - 1", "newton")
+    }
+
+    #[test]
+    fn straight_boundary_has_dimension_one() {
+        let vertical = statistics(&map(256, |_, j| (j >= 128) as u16));
+        assert!((vertical.boundary_dimension - 1.0).abs() < 1e-12, "{}", vertical.boundary_dimension);
+
+        let diagonal = statistics(&map(256, |i, j| (j > i) as u16));
+        assert!((diagonal.boundary_dimension - 1.0).abs() < 0.05, "{}", diagonal.boundary_dimension);
+    }
+
+    #[test]
+    fn half_split_gives_equal_basins() {
+        let stats = statistics(&map(64, |_, j| (j >= 32) as u16));
+        for basin in &stats.basins {
+            assert_eq!(basin.pixels, 64 * 32);
+            assert_eq!(basin.fraction, 0.5);
+            assert_eq!(basin.area, 2.0);
+        }
+        assert_eq!(stats.non_converged_fraction, 0.0);
+        assert_eq!(stats.mean_iterations(), 3.0);
+    }
+
+    #[test]
+    fn missing_boundary_gives_nan_dimension() {
+        // Jeden basen na całym obrazie: brak pikseli brzegowych
+        let single = statistics(&map(64, |_, _| 0));
+        assert!(single.box_counts.iter().all(|c| c.boxes == 0));
+        assert!(single.boundary_dimension.is_nan());
+        assert_eq!(single.basins[0].fraction, 1.0);
+
+        // Nic nie zbiegło
+        let none = statistics(&BasinMap::new(64, 64));
+        assert_eq!(none.non_converged_fraction, 1.0);
+        assert_eq!(none.mean_iterations(), 0.0);
+        assert!(none.boundary_dimension.is_nan());
+
+        // Obraz zbyt mały na choć dwa rozmiary pudełek
+        let tiny = statistics(&map(8, |_, j| (j >= 4) as u16));
+        assert!(tiny.boundary_dimension.is_nan());
+        assert!(tiny.to_csv().contains("boundary_dimension,,NaN"));
+    }
+}
@@ -0,0 +1,110 @@
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, DynamicImage, Frame};
+use num_complex::Complex;
+use std::error::Error;
+use std::fs::File;
+use std::path::Path;
+use std::time::Instant;
+use crate::double_double::DoubleDouble;
+use crate::iteration::{ComplexFunction, Iteration};
+use crate::palette::Coloring;
+use crate::{precision_label, render_fractal, FractalConfig};
+
+pub struct ZoomOptions {
+    // Środek i szerokość (wzdłuż osi rzeczywistej) ostatniej klatki
+    pub center: Complex<DoubleDouble>,
+    pub width: f64,
+    pub frames: usize,
+    // Bok klatki w pikselach
+    pub size: u32,
+    // Czas wyświetlania klatki w GIF-ie
+    pub frame_delay_ms: u32,
+}
+
+impl Default for ZoomOptions {
+    fn default() -> Self {
+        ZoomOptions {
+            // -2^(-1/3): przeciwobraz zera przy z^3 - 1, leżący na styku
+            // wszystkich trzech basenów (z dokładnością double-double)
+            center: Complex::new(
+                DoubleDouble { hi: -0.7937005259840998, lo: 5.4345040970989115e-17 },
+                DoubleDouble::new(0.0),
+            ),
+            width: 1e-3,
+            frames: 60,
+            size: 600,
+            frame_delay_ms: 80,
+        }
+    }
+}
+
+// Dodatkowe iteracje na każde dwukrotne przybliżenie: w pobliżu brzegu
+// basenów punkty przechodzą blisko biegunów odwzorowania Newtona i wracają
+// z odległości ok. 1/d^2 (przy z^3 - 1 z szybkością ok. 2/3 na krok), czyli
+// potrzebują ok. 2 log(2) / log(3/2) = 3.4 kroku więcej na oktawę
+const ITERATIONS_PER_OCTAVE: f64 = 4.0;
+
+// Obszar k-tej klatki. Szerokość maleje geometrycznie; środek przesuwa
+// się proporcjonalnie do szerokości, więc punkt docelowy zostaje w tym
+// samym miejscu ekranu i w ostatniej klatce trafia dokładnie w środek.
+fn frame_config(start: &FractalConfig, options: &ZoomOptions, k: usize) -> FractalConfig {
+    let w0 = start.span.0;
+    let aspect = start.span.1 / w0;
+
+    let t = if options.frames > 1 { k as f64 / (options.frames - 1) as f64 } else { 1.0 };
+    let width = w0 * (options.width / w0).powf(t);
+    let s = if w0 != options.width { (width - options.width) / (w0 - options.width) } else { 0.0 };
+    let center = options.center + (start.center - options.center) * DoubleDouble::new(s);
+    let extra_iterations = (ITERATIONS_PER_OCTAVE * (w0 / width).log2()).ceil().max(0.0) as u32;
+
+    FractalConfig {
+        center,
+        span: (width, width * aspect),
+        width: options.size,
+        height: ((options.size as f64 * aspect).round() as u32).max(1),
+        max_iter: start.max_iter + extra_iterations,
+        ..*start
+    }
+}
+
+// Renderuje kolejne klatki przybliżenia od obszaru `start` do środka i
+// szerokości z `options`. Każda klatka liczona jest równolegle pasami
+// wierszy, jak pojedynczy obraz. Plik .gif dostaje animację, każda inna
+// ścieżka jest katalogiem na klatki frame_0000.png, frame_0001.png, ...
+pub fn render_zoom<F>(function: &F, method: Iteration, roots: &[Complex<f64>], start: &FractalConfig, coloring: &Coloring, options: &ZoomOptions, output: &str) -> Result<(), Box<dyn Error>>
+where
+    F: ComplexFunction + ?Sized,
+{
+    let is_gif = Path::new(output).extension().is_some_and(|e| e.eq_ignore_ascii_case("gif"));
+    let mut encoder = if is_gif {
+        let mut encoder = GifEncoder::new_with_speed(File::create(output)?, 10);
+        encoder.set_repeat(Repeat::Infinite)?;
+        Some(encoder)
+    } else {
+        std::fs::create_dir_all(output)?;
+        None
+    };
+
+    println!("Przybliżenie do {} {:+}i, szerokość {:e}, {} klatek {}x{}",
+             options.center.re, options.center.im, options.width, options.frames, options.size, options.size);
+    let begin = Instant::now();
+    for k in 0..options.frames {
+        let config = frame_config(start, options, k);
+        let (img, map) = render_fractal(function, method, roots, &config, coloring);
+        println!("Klatka {:>4}/{}: szerokość {:.3e}, maks. iteracji {}, zbieżność {:.2}%, {}",
+                 k + 1, options.frames, config.span.0, config.max_iter,
+                 100.0 * map.converged() as f64 / (config.width * config.height) as f64,
+                 precision_label(function, &config));
+
+        match encoder.as_mut() {
+            Some(encoder) => {
+                let rgba = DynamicImage::ImageRgb8(img).into_rgba8();
+                encoder.encode_frame(Frame::from_parts(rgba, 0, 0, Delay::from_numer_denom_ms(options.frame_delay_ms, 1)))?;
+            }
+            None => img.save(Path::new(output).join(format!("frame_{:04}.png", k)))?,
+        }
+    }
+    println!("Animacja wygenerowana w czasie: {:?}", begin.elapsed());
+    println!("Animacja zapisana jako {}", output);
+    Ok(())
+}
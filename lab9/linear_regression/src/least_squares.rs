@@ -0,0 +1,95 @@
+use ndarray::{s, Array1, Array2};
+
+// Rozwiązanie zadania najmniejszych kwadratów min |Ax - b| przez
+// faktoryzację QR odbiciami Householdera: odbicia przekształcają A w macierz
+// trójkątną R, a b w Qᵀb, po czym Rx = (Qᵀb)[..k] rozwiązujemy wstecz
+pub fn qr_least_squares(a: &Array2<f64>, b: &Array1<f64>) -> Result<Array1<f64>, String> {
+    let (rows, cols) = a.dim();
+    if rows < cols {
+        return Err(format!("Za mało punktów ({}) na {} współczynniki", rows, cols));
+    }
+    let mut r = a.clone();
+    let mut qt_b = b.clone();
+
+    for k in 0..cols {
+        // Odbicie zerujące kolumnę k poniżej przekątnej: v = x + sign(x0)|x|e0
+        let mut v = r.slice(s![k.., k]).to_owned();
+        let norm = v.dot(&v).sqrt();
+        if norm == 0.0 {
+            return Err("Macierz układu jest osobliwa".to_string());
+        }
+        v[0] += norm.copysign(v[0]);
+        let v_norm2 = v.dot(&v);
+
+        // H = I - 2vvᵀ/(vᵀv) stosujemy do pozostałych kolumn i do b
+        for j in k..cols {
+            let factor = 2.0 * v.dot(&r.slice(s![k.., j])) / v_norm2;
+            r.slice_mut(s![k.., j]).scaled_add(-factor, &v);
+        }
+        let factor = 2.0 * v.dot(&qt_b.slice(s![k..])) / v_norm2;
+        qt_b.slice_mut(s![k..]).scaled_add(-factor, &v);
+    }
+
+    // Podstawianie wstecz w trójkątnej macierzy R (k x k)
+    let mut x = Array1::zeros(cols);
+    for i in (0..cols).rev() {
+        let sum: f64 = (i + 1..cols).map(|j| r[[i, j]] * x[j]).sum();
+        if r[[i, i]].abs() < 1e-12 * r[[0, 0]].abs() {
+            return Err("Macierz układu jest osobliwa".to_string());
+        }
+        x[i] = (qt_b[i] - sum) / r[[i, i]];
+    }
+    Ok(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn exact_fit_is_recovered() {
+        // Punkty leżą dokładnie na prostej y = 3 - 0.5x
+        let a = array![[1.0, 0.0], [1.0, 2.0]];
+        let b = array![3.0, 2.0];
+        let x = qr_least_squares(&a, &b).unwrap();
+        assert!((x[0] - 3.0).abs() < 1e-12, "{}", x);
+        assert!((x[1] + 0.5).abs() < 1e-12, "{}", x);
+    }
+
+    #[test]
+    fn overdetermined_system_matches_closed_form() {
+        let xs = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+        let ys = [1.1, 2.9, 5.2, 6.8, 9.1, 11.0];
+        let a = Array2::from_shape_fn((xs.len(), 2), |(i, j)| if j == 0 { 1.0 } else { xs[i] });
+        let b = Array1::from_vec(ys.to_vec());
+        let x = qr_least_squares(&a, &b).unwrap();
+
+        // Wzory zamknięte regresji liniowej: beta_1 = cov(x, y) / var(x)
+        let n = xs.len() as f64;
+        let x_mean = xs.iter().sum::<f64>() / n;
+        let y_mean = ys.iter().sum::<f64>() / n;
+        let cov: f64 = xs.iter().zip(&ys).map(|(x, y)| (x - x_mean) * (y - y_mean)).sum();
+        let var: f64 = xs.iter().map(|x| (x - x_mean).powi(2)).sum();
+        let beta_1 = cov / var;
+        let beta_0 = y_mean - beta_1 * x_mean;
+        assert!((x[0] - beta_0).abs() < 1e-12, "{} vs {}", x[0], beta_0);
+        assert!((x[1] - beta_1).abs() < 1e-12, "{} vs {}", x[1], beta_1);
+    }
+
+    #[test]
+    fn rank_deficient_system_is_rejected() {
+        // Wszystkie punkty mają to samo x - kolumny są liniowo zależne
+        let a = array![[1.0, 2.0], [1.0, 2.0], [1.0, 2.0]];
+        let b = array![1.0, 2.0, 3.0];
+        assert!(qr_least_squares(&a, &b).is_err());
+
+        // Zerowa kolumna
+        let a = array![[1.0, 0.0], [1.0, 0.0]];
+        assert!(qr_least_squares(&a, &array![1.0, 2.0]).is_err());
+
+        // Mniej punktów niż współczynników
+        let a = array![[1.0, 2.0]];
+        assert!(qr_least_squares(&a, &array![1.0]).is_err());
+    }
+}
@@ -1,11 +1,102 @@
+mod least_squares;
+
+use least_squares::qr_least_squares;
 use ndarray::{Array1, Array2};
-use ndarray_linalg::{QR, Solve};
 use rand_distr::{Normal, Distribution};
+use plotters::coord::Shift;
 use plotters::prelude::*;
 use std::env;
+use std::path::Path;
+use svg2pdf::usvg::{self, fontdb, PostProcessingSteps, TreeParsing, TreePostProc};
+
+const PLOT_SIZE: (u32, u32) = (1600, 1200);
+
+// Rysuje punkty danych i dopasowaną prostą na dowolnym backendzie plotters
+fn draw_regression<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    x_data: &[f64],
+    y_data: &[f64],
+    beta_0: f64,
+    beta_1: f64,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE)?;
+
+    // Znajdź min i max wartości dla skalowania wykresu
+    let x_min = *x_data.iter().min_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
+    let x_max = *x_data.iter().max_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
+    let y_min = *y_data.iter().min_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
+    let y_max = *y_data.iter().max_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
+
+    // Dodaj margines dla lepszej czytelności
+    let x_margin = (x_max - x_min) * 0.1;
+    let y_margin = (y_max - y_min) * 0.1;
+
+    let mut chart = ChartBuilder::on(root)
+        .caption("Dopasowanie liniowe (regresja QR)", ("sans-serif", 30))
+        .margin(30)
+        .x_label_area_size(40)
+        .y_label_area_size(40)
+        .build_cartesian_2d(
+            (x_min - x_margin)..(x_max + x_margin),
+            (y_min - y_margin)..(y_max + y_margin)
+        )?;
+
+    chart.configure_mesh().draw()?;
+
+    // Punkty danych
+    chart.draw_series(
+        x_data
+            .iter()
+            .zip(y_data.iter().cloned()) // Use cloned() to avoid moving y_data
+            .map(|(&x, y)| Circle::new((x, y), 1, RED.filled())),
+    )?;
+
+    // Rysuj linię regresji dokładnie w zakresie danych
+    chart.draw_series(LineSeries::new(
+        vec![
+            (x_min - x_margin, beta_0 + beta_1 * (x_min - x_margin)),
+            (x_max + x_margin, beta_0 + beta_1 * (x_max + x_margin))
+        ],
+        &BLUE,
+    ))?;
+
+    root.present()?;
+    Ok(())
+}
+
+// Zamienia wykres SVG na PDF. plotters nie ma backendu PDF, więc rysujemy
+// do SVG, a svg2pdf zamienia tekst na krzywe z czcionek systemowych - bez
+// żadnej czcionki podpisy osi by zniknęły, dlatego jest to błąd.
+// To jedyna kopia w tym crate (ta sama konwersja jest w lab11, output.rs)
+fn svg_to_pdf(svg: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut tree = usvg::Tree::from_str(svg, &usvg::Options::default())?;
+    let mut fonts = fontdb::Database::new();
+    fonts.load_system_fonts();
+    if fonts.is_empty() {
+        return Err("Brak czcionek systemowych - nie można zapisać wykresu do PDF".into());
+    }
+    // fontdb domyślnie szuka Ariala; jeśli go nie ma, bierzemy inną czcionkę bezszeryfową
+    let sans_serif = fontdb::Query { families: &[fontdb::Family::SansSerif], ..Default::default() };
+    if fonts.query(&sans_serif).is_none() {
+        let families: Vec<String> = fonts.faces()
+            .filter_map(|face| face.families.first().map(|(name, _)| name.clone()))
+            .collect();
+        let fallback = families.iter()
+            .find(|name| name.contains("Sans") && !name.contains("Mono"))
+            .or(families.first());
+        if let Some(family) = fallback.cloned() {
+            fonts.set_sans_serif_family(family);
+        }
+    }
+    tree.postprocess(PostProcessingSteps::default(), &fonts);
+    Ok(svg2pdf::convert_tree(&tree, svg2pdf::Options::default()))
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Pobierz liczbę punktów z argumentów wiersza poleceń
+    // Pobierz liczbę punktów i nazwę pliku z wykresem z argumentów wiersza poleceń
     let args: Vec<String> = env::args().collect();
     let num_points = if args.len() > 1 {
         match args[1].parse::<usize>() {
@@ -20,6 +111,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         500
     };
 
+    // Format wykresu wynika z rozszerzenia: .png, .svg albo .pdf
+    let plot_filename = args.get(2).cloned().unwrap_or_else(|| "plot.png".to_string());
+    let extension = Path::new(&plot_filename)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    if !["png", "svg", "pdf"].contains(&extension.as_str()) {
+        return Err(format!("Nieobsługiwany format wykresu: {}", plot_filename).into());
+    }
+
     println!("Generuję {} punktów do regresji liniowej", num_points);
 
     // Przykładowe dane do regresji liniowej
@@ -44,60 +146,34 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let b = Array1::from_vec(y_data.clone());
 
     // Faktoryzacja QR i rozwiązanie Rx = Qᵀb
-    let (q, r) = a.qr()?;  // QR returns a tuple (Q, R) directly
-    let qt_b = q.t().dot(&b);
-    let x = r.solve_into(qt_b)?;
+    let x = qr_least_squares(&a, &b)?;
 
     println!("Współczynniki regresji (beta_0, beta_1): {:?}", x);
 
-    // Wizualizacja danych i dopasowanej prostej
-    let root = BitMapBackend::new("plot.png", (1600, 1200)).into_drawing_area();
-    root.fill(&WHITE)?;
-
-    // Znajdź min i max wartości dla skalowania wykresu
-    let x_min = *x_data.iter().min_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
-    let x_max = *x_data.iter().max_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
-    let y_min = *y_data.iter().min_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
-    let y_max = *y_data.iter().max_by(|a, b| a.partial_cmp(b).unwrap()).unwrap();
-
-    // Dodaj margines dla lepszej czytelności
-    let x_margin = (x_max - x_min) * 0.1;
-    let y_margin = (y_max - y_min) * 0.1;
-
-    let mut chart = ChartBuilder::on(&root)
-        .caption("Dopasowanie liniowe (regresja QR)", ("sans-serif", 30))
-        .margin(30)
-        .x_label_area_size(40)
-        .y_label_area_size(40)
-        .build_cartesian_2d(
-            (x_min - x_margin)..(x_max + x_margin),
-            (y_min - y_margin)..(y_max + y_margin)
-        )?;
-
-    chart.configure_mesh().draw()?;
-
-    // Punkty danych
-    chart.draw_series(
-        x_data
-            .iter()
-            .zip(y_data.iter().cloned()) // Use cloned() to avoid moving y_data
-            .map(|(&x, y)| Circle::new((x, y), 1, RED.filled())),
-    )?;
-
-    // Linia regresji: y = beta_0 + beta_1 * x
+    // Wizualizacja danych i dopasowanej prostej: y = beta_0 + beta_1 * x
     let beta_0 = x[0];
     let beta_1 = x[1];
 
-    // Rysuj linię regresji dokładnie w zakresie danych
-    chart.draw_series(LineSeries::new(
-        vec![
-            (x_min - x_margin, beta_0 + beta_1 * (x_min - x_margin)),
-            (x_max + x_margin, beta_0 + beta_1 * (x_max + x_margin))
-        ],
-        &BLUE,
-    ))?;
+    match extension.as_str() {
+        "svg" => {
+            let root = SVGBackend::new(&plot_filename, PLOT_SIZE).into_drawing_area();
+            draw_regression(&root, &x_data, &y_data, beta_0, beta_1)?;
+        }
+        "pdf" => {
+            let mut svg = String::new();
+            {
+                let root = SVGBackend::with_string(&mut svg, PLOT_SIZE).into_drawing_area();
+                draw_regression(&root, &x_data, &y_data, beta_0, beta_1)?;
+            }
+            std::fs::write(&plot_filename, svg_to_pdf(&svg)?)?;
+        }
+        _ => {
+            let root = BitMapBackend::new(&plot_filename, PLOT_SIZE).into_drawing_area();
+            draw_regression(&root, &x_data, &y_data, beta_0, beta_1)?;
+        }
+    }
 
-    println!("Wykres zapisany do plot.png");
+    println!("Wykres zapisany do {}", plot_filename);
 
     Ok(())
 }